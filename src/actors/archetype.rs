@@ -0,0 +1,209 @@
+use super::{Boss, Chicken, Farmer, Spider, WhiteWolf, Wolf};
+use crate::{
+    ai::{AnimationSet, AnimationState, Animator, Behaviour, Brain, Clip, Facing},
+    assets::GameAssets,
+    combat::Health,
+    factions::Faction,
+    fov::FieldOfView,
+    interactions::Interaction,
+    items::{Loot, LootTable},
+    maps::{tile_to_screen, MapElement, TilePosition},
+    status::StatusEffects,
+    turns::{Initiative, ACTION_COST},
+    GameElement,
+};
+use bevy::{ecs::system::EntityCommands, prelude::*};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// Embedded rather than loaded, so they work the same on the web build
+const ARCHETYPE_FILES: [&str; 5] = [
+    include_str!("../../assets/archetypes/chicken.ron"),
+    include_str!("../../assets/archetypes/farmer.ron"),
+    include_str!("../../assets/archetypes/weak_wolf.ron"),
+    include_str!("../../assets/archetypes/white_wolf.ron"),
+    include_str!("../../assets/archetypes/spider.ron"),
+];
+
+#[derive(Deserialize, Clone, Copy)]
+pub enum Sheet {
+    Chick,
+    Doggies,
+    Spiders,
+    Tom,
+}
+
+#[derive(Deserialize)]
+pub struct SpriteDef {
+    pub sheet: Sheet,
+    pub frame: usize,
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+fn default_speed() -> i32 {
+    ACTION_COST
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub enum Tag {
+    Chicken,
+    Farmer,
+    Wolf,
+    WhiteWolf,
+    Spider,
+}
+
+impl Tag {
+    fn insert_marker(&self, entity: &mut EntityCommands) {
+        match self {
+            Tag::Chicken => entity.insert(Chicken),
+            Tag::Farmer => entity.insert(Farmer(false)),
+            Tag::Wolf => entity.insert(Wolf),
+            Tag::WhiteWolf => entity.insert(WhiteWolf).insert(Boss::default()),
+            Tag::Spider => entity.insert(Spider),
+        };
+    }
+}
+
+#[derive(Deserialize)]
+pub struct Archetype {
+    pub name: String,
+    pub sprite: SpriteDef,
+    #[serde(default)]
+    pub animations: HashMap<AnimationState, Clip>,
+    pub health: i32,
+    /// Energy gained per round in turn-based mode
+    #[serde(default = "default_speed")]
+    pub speed: i32,
+    pub fov_range: i32,
+    #[serde(default)]
+    pub dark_vision: bool,
+    pub faction: Faction,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    #[serde(default)]
+    pub behaviours: Vec<Behaviour>,
+    #[serde(default)]
+    pub interactions: Vec<(String, (f32, f32, f32))>,
+    /// Items and their percentage chance of dropping on death
+    #[serde(default)]
+    pub loot: Vec<(Loot, i32)>,
+}
+
+pub struct Archetypes {
+    archetypes: Vec<Archetype>,
+}
+
+impl Archetypes {
+    pub fn load() -> Self {
+        let archetypes = ARCHETYPE_FILES
+            .iter()
+            .map(|file| ron::from_str(file).expect("Unable to parse actor archetype"))
+            .collect();
+        Self { archetypes }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Archetype> {
+        self.archetypes.iter().find(|a| a.name == name)
+    }
+}
+
+/// Spawns any actor described by an archetype file. Returns false if there is no such actor.
+pub fn spawn_actor(
+    name: &str,
+    x: i32,
+    y: i32,
+    archetypes: &Archetypes,
+    assets: &GameAssets,
+    commands: &mut Commands,
+) -> bool {
+    let archetype = if let Some(archetype) = archetypes.get(name) {
+        archetype
+    } else {
+        return false;
+    };
+
+    let pos = tile_to_screen(x, y);
+    let mut transform = Transform::from_xyz(pos.0, pos.1, 2.0);
+    transform.scale = Vec3::splat(archetype.sprite.scale);
+
+    let atlas = match archetype.sprite.sheet {
+        Sheet::Chick => Some(assets.chick.clone()),
+        Sheet::Doggies => Some(assets.doggies.clone()),
+        Sheet::Spiders => Some(assets.spiders.clone()),
+        Sheet::Tom => None,
+    };
+    let mut entity = if let Some(texture_atlas) = atlas {
+        commands.spawn_bundle(SpriteSheetBundle {
+            texture_atlas,
+            transform,
+            sprite: TextureAtlasSprite::new(archetype.sprite.frame),
+            ..default()
+        })
+    } else {
+        commands.spawn_bundle(SpriteBundle {
+            texture: assets.tom.clone(),
+            transform,
+            ..default()
+        })
+    };
+
+    entity
+        .insert(TilePosition { x, y })
+        .insert(Health {
+            current: archetype.health,
+            max: archetype.health,
+        })
+        .insert(if archetype.dark_vision {
+            FieldOfView::new(archetype.fov_range).with_dark_vision()
+        } else {
+            FieldOfView::new(archetype.fov_range)
+        })
+        .insert(archetype.faction)
+        .insert(Initiative::new(archetype.speed))
+        .insert(Brain::default())
+        .insert(StatusEffects::default())
+        .insert(MapElement)
+        .insert(GameElement);
+
+    if !archetype.animations.is_empty() {
+        entity
+            .insert(AnimationSet {
+                clips: archetype
+                    .animations
+                    .iter()
+                    .map(|(state, clip)| (*state, clip.clone()))
+                    .collect(),
+            })
+            .insert(Animator::new(Facing::Down));
+    }
+    if !archetype.interactions.is_empty() {
+        entity.insert(Interaction {
+            output: archetype
+                .interactions
+                .iter()
+                .map(|(text, (r, g, b))| (text.clone(), Color::rgb(*r, *g, *b)))
+                .collect(),
+        });
+    }
+
+    if !archetype.loot.is_empty() {
+        entity.insert(LootTable(archetype.loot.clone()));
+    }
+
+    archetype
+        .tags
+        .iter()
+        .for_each(|tag| tag.insert_marker(&mut entity));
+    archetype
+        .behaviours
+        .iter()
+        .for_each(|behaviour| behaviour.insert_marker(&mut entity));
+
+    true
+}
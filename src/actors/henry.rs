@@ -1,11 +1,18 @@
-use super::{Player, ScaresChickens, Tasty};
+use super::Player;
 use crate::{
-    ai::{Action, ActionRequest, AnimationSet, Facing},
+    ai::{
+        in_path_of, Action, ActionRequest, AnimationSet, AnimationState, Animator, Brain, Clip,
+        CompanionOrder, Facing, Follows, Hunts, Intent, FOLLOW_DISTANCE,
+    },
     assets::GameAssets,
-    combat::{Health, Hostile, LerpAttack, Unconscious},
+    combat::{Health, LerpAttack},
+    console::Console,
+    factions::Faction,
     fov::FieldOfView,
     interactions::Interaction,
-    maps::{tile_to_screen, LerpMove, RegionMap, TilePosition, NUM_TILES_X, NUM_TILES_Y},
+    maps::{tile_to_screen, LerpMove, Navigator, Occupancy, RegionMap, TilePosition},
+    status::{Effect, StatusEffects},
+    turns::Initiative,
     GameElement, TimeStepResource,
 };
 use bevy::prelude::*;
@@ -15,6 +22,25 @@ pub struct Henry {
     facing: Facing,
 }
 
+/// How long Henry stays out cold if nobody helps him up (ten seconds)
+pub const KNOCKOUT_TICKS: u32 = 300;
+/// Each knockout costs Henry this much max health until he rests it off
+const INJURY_PENALTY: i32 = 2;
+const MIN_MAX_HEALTH: i32 = 2;
+/// Ticks of lying around it takes to heal one injury
+const REST_TICKS: u32 = 600;
+
+/// Knockouts stack up; resting heals them one at a time
+#[derive(Component, Default)]
+pub struct Injuries {
+    pub stacks: i32,
+    /// Max health actually taken so far, which can be less than a full penalty per stack
+    lost: i32,
+    rested: u32,
+    /// Whether he was out cold last we looked, to catch him going down and coming round
+    knocked_out: bool,
+}
+
 pub fn spawn_henry(commands: &mut Commands, assets: &GameAssets, start: (i32, i32)) {
     let pos = tile_to_screen(start.0, start.1);
 
@@ -43,24 +69,53 @@ pub fn spawn_henry(commands: &mut Commands, assets: &GameAssets, start: (i32, i3
             ],
         })
         .insert(FieldOfView::new(8))
-        .insert(ScaresChickens)
+        .insert(Faction::Companion)
+        .insert(Hunts)
+        .insert(Follows)
+        .insert(Brain::default())
+        .insert(CompanionOrder::Follow)
+        .insert(Injuries::default())
+        .insert(StatusEffects::default())
+        // Dogs are quicker than chickens
+        .insert(Initiative::new(12))
         .insert(Health {
             current: 10,
             max: 10,
         })
-        .insert(AnimationSet {
-            animations: vec![
-                // Left
-                vec![56, 57, 58],
-                // Right
-                vec![8, 9, 10],
-                // Up
-                vec![24, 25, 26],
-                // Down
-                vec![72, 73, 74],
-            ],
-        })
-        .insert(Tasty)
+        .insert(
+            AnimationSet::default()
+                .with(
+                    AnimationState::Walk,
+                    Clip::new(vec![
+                        // Left
+                        vec![56, 57, 58],
+                        // Right
+                        vec![8, 9, 10],
+                        // Up
+                        vec![24, 25, 26],
+                        // Down
+                        vec![72, 73, 74],
+                    ])
+                    .looping(),
+                )
+                // Leaping
+                .with(AnimationState::Jump, Clip::new(vec![vec![43]]))
+                .with(
+                    AnimationState::Attack,
+                    Clip::new(vec![vec![43]]).ticks_per_frame(4),
+                )
+                // Cowering
+                .with(
+                    AnimationState::Hurt,
+                    Clip::new(vec![vec![27]]).ticks_per_frame(6),
+                )
+                // Lying on his side
+                .with(
+                    AnimationState::Unconscious,
+                    Clip::new(vec![vec![59], vec![11], vec![11], vec![11]]),
+                ),
+        )
+        .insert(Animator::new(Facing::Right))
         .insert(GameElement);
 }
 
@@ -70,108 +125,193 @@ pub fn distance(pos1: &TilePosition, pos2: &TilePosition) -> f32 {
     f32::sqrt((dx * dx) + (dy * dy))
 }
 
-pub fn unconscious_henry(
-    mut query: Query<
-        (
-            Entity,
-            &mut Unconscious,
-            &mut Health,
-            &mut TextureAtlasSprite,
-        ),
-        With<Henry>,
-    >,
-    mut commands: Commands,
+/// Henry gets back up with a clean bill of health, less his injuries
+pub fn wake_henry(mut query: Query<(&StatusEffects, &mut Injuries, &mut Health), With<Henry>>) {
+    for (effects, mut injuries, mut health) in query.iter_mut() {
+        if injuries.knocked_out && !effects.has(Effect::Unconscious) {
+            injuries.knocked_out = false;
+            health.current = health.max;
+        }
+    }
+}
+
+pub fn injure_henry(
+    mut query: Query<(&StatusEffects, &mut Injuries, &mut Health), With<Henry>>,
+    console: Res<Console>,
+) {
+    for (effects, mut injuries, mut health) in query.iter_mut() {
+        if injuries.knocked_out || !effects.has(Effect::Unconscious) {
+            continue;
+        }
+        injuries.knocked_out = true;
+        let penalty = i32::min(INJURY_PENALTY, health.max - MIN_MAX_HEALTH).max(0);
+        injuries.stacks += 1;
+        injuries.lost += penalty;
+        injuries.rested = 0;
+        health.max -= penalty;
+        health.current = health.current.min(health.max);
+        console.write(
+            "Henry is knocked out! Feed him some grain to bring him round.",
+            Color::RED,
+        );
+    }
+}
+
+/// Lying still heals injuries; getting into a scrap starts the rest over
+pub fn rest_henry(
+    mut query: Query<(&mut Injuries, &mut Health, &Brain), With<Henry>>,
+    console: Res<Console>,
     timer: Res<TimeStepResource>,
 ) {
     if !timer.timer.finished() {
         return;
     }
-    for (henry, mut unconscious, mut health, mut sprite) in query.iter_mut() {
-        if unconscious.0 == 0 {
-            health.current = health.max;
-            commands.entity(henry).remove::<Unconscious>();
-            sprite.index = 8;
-        } else {
-            unconscious.0 -= 1;
-            sprite.index = 11;
+    for (mut injuries, mut health, brain) in query.iter_mut() {
+        if injuries.stacks == 0 || injuries.knocked_out {
+            continue;
+        }
+        match brain.intent {
+            Intent::Idle => injuries.rested += 1,
+            Intent::Attack(_) | Intent::Chase(_) | Intent::Flee => injuries.rested = 0,
+            _ => {}
+        }
+        if injuries.rested >= REST_TICKS {
+            injuries.rested = 0;
+            injuries.stacks -= 1;
+            // Never back past where he started
+            let restored = i32::min(INJURY_PENALTY, injuries.lost);
+            injuries.lost -= restored;
+            health.max += restored;
+            health.current = i32::min(health.current + restored, health.max);
+            console.write("Henry looks a bit better after his rest.", Color::YELLOW);
         }
     }
 }
 
+/// Any further than this and Henry just bounds over to catch up
+const TELEPORT_DISTANCE: f32 = 12.0;
+
 pub fn henry_ai(
     mut queries: ParamSet<(
-        Query<&TilePosition, With<Player>>,
+        Query<(&TilePosition, &Player)>,
         Query<
-            (Entity, &mut Henry, &TilePosition, &FieldOfView),
-            (Without<LerpMove>, Without<LerpAttack>, Without<Unconscious>),
+            (Entity, &mut Henry, &mut TilePosition, &Brain),
+            (Without<LerpMove>, Without<LerpAttack>),
         >,
-        Query<(Entity, &TilePosition), (With<Hostile>, Without<Unconscious>)>,
     )>,
     map: Res<RegionMap>,
+    occupancy: Res<Occupancy>,
+    console: Res<Console>,
     mut actions: EventWriter<ActionRequest>,
     timer: Res<TimeStepResource>,
 ) {
     if !timer.timer.finished() {
         return;
     }
-    let player_pos = queries.p0().single().clone();
-    for (entity, mut henry, henry_pos, _) in queries.p1().iter_mut() {
+    let (player_pos, player_facing) = {
+        let player = queries.p0();
+        let (pos, player) = player.single();
+        (pos.clone(), player.facing)
+    };
+    let there = (player_pos.x, player_pos.y);
+    for (entity, mut henry, mut henry_pos, brain) in queries.p1().iter_mut() {
+        if !matches!(brain.intent, Intent::Follow(_)) {
+            continue;
+        }
+        let here = (henry_pos.x, henry_pos.y);
         let distance = distance(&henry_pos, &player_pos);
-        if distance > 1.6 {
-            let x = henry_pos.x;
-            let y = henry_pos.y;
-            let mut jumping = false;
 
-            let delta = if x < player_pos.x && map.can_player_enter(x + 1, y) {
-                henry.facing = Facing::Right;
-                (1, 0)
-            } else if x > player_pos.x && map.can_player_enter(x - 1, y) {
-                henry.facing = Facing::Left;
-                (-1, 0)
-            } else if y < player_pos.y && map.can_player_enter(x, y + 1) {
-                henry.facing = Facing::Down;
-                (0, 1)
-            } else if y > player_pos.y && map.can_player_enter(x, y - 1) {
-                henry.facing = Facing::Up;
-                (0, -1)
-            } else if x < player_pos.x && map.can_player_enter(x + 2, y) {
-                henry.facing = Facing::Right;
-                jumping = true;
-                (2, 0)
-            } else if x > player_pos.x && map.can_player_enter(x - 2, y) {
-                henry.facing = Facing::Left;
-                jumping = true;
-                (-2, 0)
-            } else if y < player_pos.y && map.can_player_enter(x, y + 2) {
-                henry.facing = Facing::Down;
-                jumping = true;
-                (0, 2)
-            } else if y > player_pos.y && map.can_player_enter(x, y - 2) {
-                henry.facing = Facing::Up;
-                jumping = true;
-                (0, -2)
-            // LEAPING
+        let step = if in_path_of(there, player_facing, here) {
+            // Don't be a tripping hazard
+            step_aside(&map, &occupancy, entity, here, there, player_facing)
+        } else if distance > FOLLOW_DISTANCE {
+            let path = if distance > TELEPORT_DISTANCE {
+                None
             } else {
-                (0, 0)
+                Navigator::new(&map)
+                    .with_jumps()
+                    .avoiding(&occupancy, entity)
+                    .path(here, there)
             };
-
-            if delta != (0, 0) {
-                let destination = (
-                    (x + delta.0).clamp(0, NUM_TILES_X as i32 - 1),
-                    (y + delta.1).clamp(0, NUM_TILES_Y as i32 - 1),
-                );
-                if map.can_player_enter(destination.0, destination.1) {
-                    actions.send(ActionRequest {
-                        entity,
-                        action: Action::Move {
-                            from: (henry_pos.x, henry_pos.y),
-                            to: destination,
-                            jumping,
-                        },
-                        priority: 1,
-                    });
+            match path {
+                Some(path) => path.first().copied().filter(|step| *step != there),
+                None => {
+                    let landing = catch_up_spot(&map, &occupancy, entity, there, player_facing);
+                    if let Some(landing) = landing {
+                        henry_pos.x = landing.0;
+                        henry_pos.y = landing.1;
+                        console.write("Henry bounds over to catch up", Color::YELLOW);
+                    }
+                    continue;
                 }
             }
+        } else {
+            None
+        };
+
+        if let Some(to) = step {
+            let delta = (to.0 - here.0, to.1 - here.1);
+            henry.facing = Facing::from_delta(delta.0, delta.1);
+            actions.send(ActionRequest {
+                entity,
+                action: Action::Move {
+                    from: here,
+                    to,
+                    jumping: delta.0.abs() + delta.1.abs() > 1,
+                },
+                priority: 1,
+            });
         }
     }
 }
+
+/// The neighbouring tile that is out of the player's way, but still closest to them
+fn step_aside(
+    map: &RegionMap,
+    occupancy: &Occupancy,
+    me: Entity,
+    here: (i32, i32),
+    player: (i32, i32),
+    facing: Facing,
+) -> Option<(i32, i32)> {
+    [(-1, 0), (1, 0), (0, -1), (0, 1)]
+        .iter()
+        .map(|(dx, dy)| (here.0 + dx, here.1 + dy))
+        .filter(|pos| {
+            !occupancy.is_blocked(pos.0, pos.1, me)
+                && map.can_player_enter(pos.0, pos.1)
+                && !in_path_of(player, facing, *pos)
+        })
+        .min_by_key(|pos| (pos.0 - player.0).abs() + (pos.1 - player.1).abs())
+}
+
+/// Somewhere next to the player, preferably behind them
+fn catch_up_spot(
+    map: &RegionMap,
+    occupancy: &Occupancy,
+    me: Entity,
+    player: (i32, i32),
+    facing: Facing,
+) -> Option<(i32, i32)> {
+    let behind = (player.0 - facing.delta().0, player.1 - facing.delta().1);
+    std::iter::once(behind)
+        .chain(
+            [
+                (-1, 0),
+                (1, 0),
+                (0, -1),
+                (0, 1),
+                (-1, -1),
+                (1, -1),
+                (-1, 1),
+                (1, 1),
+            ]
+            .iter()
+            .map(|(dx, dy)| (player.0 + dx, player.1 + dy)),
+        )
+        .find(|pos| {
+            map.can_player_enter(pos.0, pos.1)
+                && !occupancy.is_blocked(pos.0, pos.1, me)
+                && !in_path_of(player, facing, *pos)
+        })
+}
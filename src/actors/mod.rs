@@ -1,3 +1,4 @@
+mod archetype;
 mod farmer;
 mod henry;
 mod normal_chicken;
@@ -5,6 +6,8 @@ mod player;
 mod spikes;
 mod wolf;
 mod spider;
+mod white_wolf;
+pub use archetype::*;
 pub use farmer::*;
 pub use henry::*;
 pub use normal_chicken::*;
@@ -12,3 +15,4 @@ pub use player::*;
 pub use spikes::*;
 pub use wolf::*;
 pub use spider::*;
+pub use white_wolf::*;
@@ -1,111 +1,59 @@
+use super::Player;
 use crate::{
-    ai::{Action, ActionRequest},
-    assets::GameAssets,
-    combat::{Dead, Health, LerpAttack},
-    fov::FieldOfView,
+    ai::{Recruited, Wanders},
+    combat::Dead,
+    console::Console,
+    factions::Faction,
     interactions::Interaction,
-    maps::{tile_to_screen, LerpMove, MapElement, RegionMap, TilePosition},
-    random::Rng,
-    GameElement, TimeStepResource,
+    items::Inventory,
+    maps::{MapElement, TilePosition},
 };
 use bevy::prelude::*;
 
-use super::Tasty;
-
 #[derive(Component)]
 pub struct Chicken;
 
-#[derive(Component)]
-pub struct ScaresChickens;
-
-pub fn spawn_chicken(x: i32, y: i32, assets: &GameAssets, commands: &mut Commands) {
-    let pos = tile_to_screen(x, y);
-
-    commands
-        .spawn_bundle(SpriteSheetBundle {
-            texture_atlas: assets.chick.clone(),
-            transform: Transform::from_xyz(pos.0, pos.1, 2.0),
-            sprite: TextureAtlasSprite::new(2),
-            ..default()
-        })
-        .insert(TilePosition { x, y })
-        .insert(Chicken)
-        .insert(Interaction {
-            output: vec![(
-                "The chicken clucks. It lacks the heart of a mega-chicken.".to_string(),
-                Color::WHITE,
-            )],
-        })
-        .insert(FieldOfView::new(3))
-        .insert(MapElement)
-        .insert(Health { current: 1, max: 1 })
-        .insert(Tasty)
-        .insert(GameElement); // Don't persist chickens between levels
-}
-
-pub fn chicken_ai(
-    map: Res<RegionMap>,
-    mut ai_query: Query<
-        (Entity, &TilePosition, &mut TextureAtlasSprite),
-        (
-            With<Chicken>,
-            Without<LerpMove>,
-            Without<LerpAttack>,
-            Without<Dead>,
-        ),
-    >,
-    rng: Res<Rng>,
-    mut actions: EventWriter<ActionRequest>,
-    timer: Res<TimeStepResource>,
+/// Offering grain to a chicken you're facing wins it over to your flock
+pub fn recruit_chickens(
+    mut player: Query<(&Player, &TilePosition, &mut Inventory)>,
+    chickens: Query<(Entity, &TilePosition), (With<Chicken>, Without<Recruited>, Without<Dead>)>,
+    mut keyboard: ResMut<Input<KeyCode>>,
+    mut commands: Commands,
+    console: Res<Console>,
 ) {
-    if !timer.timer.finished() {
+    if !keyboard.just_pressed(KeyCode::Space) {
         return;
     }
-    let mut delta = None;
-    for (entity, pos, mut sprite) in ai_query.iter_mut() {
-        // Do chicken things
-        if delta.is_none() {
-            match rng.range(0, 100) {
-                1 => sprite.index = 0,
-                2 => sprite.index = 1,
-                3 => sprite.index = 2,
-                4 => sprite.index = 26,
-                6 => {
-                    if map.can_player_enter(pos.x - 1, pos.y) {
-                        delta = Some((-1, 0))
-                    }
-                }
-                7 => {
-                    if map.can_player_enter(pos.x + 1, pos.y) {
-                        delta = Some((1, 0))
-                    }
-                }
-                8 => {
-                    if map.can_player_enter(pos.x, pos.y - 1) {
-                        delta = Some((0, -1))
-                    }
-                }
-                9 => {
-                    if map.can_player_enter(pos.x, pos.y + 1) {
-                        delta = Some((0, 1))
-                    }
-                }
-                _ => {}
-            }
+    for (player, pos, mut inventory) in player.iter_mut() {
+        if inventory.grain < 1 {
+            continue;
         }
-
-        if let Some(delta) = delta {
-            if map.can_player_enter(pos.x + delta.0, pos.y + delta.1) {
-                actions.send(ActionRequest {
-                    entity,
-                    action: Action::Move {
-                        from: (pos.x, pos.y),
-                        to: (pos.x + delta.0, pos.y + delta.1),
-                        jumping: false,
-                    },
-                    priority: 1,
-                });
-            }
+        let (dx, dy) = player.facing.delta();
+        let target = (pos.x + dx, pos.y + dy);
+        let chicken = chickens
+            .iter()
+            .find(|(_, chicken_pos)| (chicken_pos.x, chicken_pos.y) == target);
+        if let Some((chicken, _)) = chicken {
+            inventory.grain -= 1;
+            // Flock members aren't part of the map any more, so they come along through exits
+            commands
+                .entity(chicken)
+                .insert(Recruited)
+                .insert(Faction::Flock)
+                .insert(Interaction {
+                    output: vec![
+                        ("The chicken clucks adoringly.".to_string(), Color::YELLOW),
+                        ("The chicken pecks at your feet.".to_string(), Color::YELLOW),
+                    ],
+                })
+                .remove::<Wanders>()
+                .remove::<MapElement>();
+            console.write(
+                "The chicken gobbles up your grain and joins your flock!",
+                Color::YELLOW,
+            );
+            // Don't let the same press chat to it as well
+            keyboard.clear_just_pressed(KeyCode::Space);
         }
     }
 }
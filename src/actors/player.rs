@@ -1,12 +1,22 @@
-use super::Tasty;
 use crate::{
-    ai::{Action, ActionRequest, AnimationSet, Facing},
+    ai::{
+        Action, ActionRequest, AnimationSet, AnimationState, Animator, Brain, Clip, Facing,
+        FlockBonus,
+    },
     assets::GameAssets,
     combat::Health,
+    console::Console,
+    factions::Faction,
+    fov::FieldOfView,
+    items::Inventory,
+    lighting::LightSource,
     maps::RegionMap,
     maps::{
         tile_index, tile_to_screen, LerpMove, TilePosition, TileType, NUM_TILES_X, NUM_TILES_Y,
     },
+    status::StatusEffects,
+    stealth::{Stealth, SNEAK_PAUSE},
+    turns::{Initiative, Turns, ACTION_COST},
     GameElement, GameState,
 };
 use bevy::prelude::*;
@@ -36,33 +46,64 @@ pub fn spawn_player(commands: &mut Commands, assets: &GameAssets, start: (i32, i
             current: 10,
             max: 10,
         })
-        .insert(Tasty)
-        .insert(AnimationSet {
-            animations: vec![
-                // Left
-                vec![12, 13, 14, 15, 16, 17],
-                // Right
-                vec![0, 1, 2, 3, 4, 5],
-                // Up
-                vec![6, 7, 8, 9, 10, 11],
-                // Down
-                vec![18, 19, 20, 21, 22, 23],
-            ],
+        .insert(Faction::Player)
+        // Only ever decides to auto-attack; everything else is up to you
+        .insert(Brain::default())
+        .insert(FieldOfView::new(10))
+        .insert(Inventory::default())
+        .insert(Stealth::default())
+        .insert(FlockBonus::default())
+        .insert(StatusEffects::default())
+        .insert(Initiative::new(ACTION_COST))
+        // Mega chickens carry a small torch, for the dark places
+        .insert(LightSource {
+            radius: 3,
+            intensity: 0.7,
+            color: Color::rgb(1.0, 0.8, 0.5),
         })
+        .insert(
+            AnimationSet::default().with(
+                AnimationState::Walk,
+                Clip::new(vec![
+                    // Left
+                    vec![12, 13, 14, 15, 16, 17],
+                    // Right
+                    vec![0, 1, 2, 3, 4, 5],
+                    // Up
+                    vec![6, 7, 8, 9, 10, 11],
+                    // Down
+                    vec![18, 19, 20, 21, 22, 23],
+                ])
+                .looping(),
+            ),
+        )
+        .insert(Animator::new(Facing::Left))
         .insert(GameElement);
 }
 
 pub fn player_movement(
     mut player: Query<
-        (Entity, &mut Player, &TilePosition, &mut TextureAtlasSprite),
+        (
+            Entity,
+            &mut Player,
+            &TilePosition,
+            &mut Animator,
+            &mut Stealth,
+        ),
         Without<LerpMove>,
     >,
     keyboard: Res<Input<KeyCode>>,
     map: Res<RegionMap>,
     mut actions: EventWriter<ActionRequest>,
     mut state: ResMut<State<GameState>>,
+    console: Res<Console>,
+    turns: Res<Turns>,
 ) {
-    for (entity, mut player, tile_pos, mut sprite) in player.iter_mut() {
+    for (entity, mut player, tile_pos, mut animator, mut stealth) in player.iter_mut() {
+        // Sneaking means taking your time between steps
+        if stealth.pause > 0 {
+            continue;
+        }
         let mut jumping = false;
         let delta: (i32, i32) = if keyboard.pressed(KeyCode::Left) || keyboard.pressed(KeyCode::A) {
             player.facing = Facing::Left;
@@ -88,19 +129,24 @@ pub fn player_movement(
             (0, 0)
         };
 
-        sprite.index = match player.facing {
-            Facing::Left => 12,
-            Facing::Right => 0,
-            Facing::Up => 6,
-            Facing::Down => 18,
-        };
+        animator.facing = player.facing;
 
         if delta != (0, 0) {
             let destination = (
                 (tile_pos.x + delta.0).clamp(0, NUM_TILES_X as i32 - 1),
                 (tile_pos.y + delta.1).clamp(0, NUM_TILES_Y as i32 - 1),
             );
-            if map.can_player_enter(destination.0, destination.1) {
+            if jumping && !map.can_jump((tile_pos.x, tile_pos.y), destination) {
+                console.write("You can't jump that! *BONK*", Color::ORANGE);
+                actions.send(ActionRequest {
+                    entity,
+                    action: Action::Bump {
+                        from: (tile_pos.x, tile_pos.y),
+                        toward: (tile_pos.x + delta.0 / 2, tile_pos.y + delta.1 / 2),
+                    },
+                    priority: 1,
+                });
+            } else if map.can_player_enter(destination.0, destination.1) {
                 if map.features[tile_index(destination.0, destination.1)] == TileType::GoldEgg {
                     let _ = state.set(GameState::Won);
                 } else {
@@ -113,6 +159,10 @@ pub fn player_movement(
                         },
                         priority: 1,
                     });
+                    // Turns already take as long as you like
+                    if stealth.sneaking && !turns.is_turn_based() {
+                        stealth.pause = SNEAK_PAUSE;
+                    }
                 }
             }
         }
@@ -1,8 +1,9 @@
 use crate::{
     assets::GameAssets,
-    combat::DamageMessage,
+    combat::{DamageMessage, Health},
     maps::{tile_to_screen, MapElement, TilePosition},
-    GameElement,
+    noise::{Noise, SPIKE_NOISE},
+    GameElement, TimeStepResource,
 };
 use bevy::prelude::*;
 
@@ -27,15 +28,22 @@ pub fn spawn_spikes(x: i32, y: i32, extended: bool, assets: &GameAssets, command
 
 pub fn spike_system(
     mut query: Query<(&mut Spike, &mut TextureAtlasSprite, &TilePosition)>,
-    ouch: Query<(Entity, &TilePosition)>,
-    time: Res<Time>,
+    ouch: Query<(Entity, &TilePosition), With<Health>>,
     mut damage: EventWriter<DamageMessage>,
+    mut noises: EventWriter<Noise>,
+    timer: Res<TimeStepResource>,
 ) {
+    // Spikes keep world time, so they stand still while a turn-based world waits on you
+    if !timer.timer.finished() {
+        return;
+    }
     for (mut spike, mut sprite, spike_pos) in query.iter_mut() {
-        spike.0.tick(time.delta());
+        spike.0.tick(timer.timer.duration());
+        let mut snapped = false;
         if spike.0.finished() {
             if sprite.index == 0 {
                 sprite.index = 1;
+                snapped = true;
             } else {
                 sprite.index = 0;
             }
@@ -47,7 +55,15 @@ pub fn spike_system(
                     damage.send(DamageMessage {
                         from: None,
                         to: victim,
-                    })
+                    });
+                    // Whoever got caught lets everyone know about it
+                    if snapped {
+                        noises.send(Noise {
+                            position: (pos.x, pos.y),
+                            loudness: SPIKE_NOISE,
+                            source: victim,
+                        });
+                    }
                 }
             }
         }
@@ -0,0 +1,418 @@
+use super::{spawn_actor, Archetypes, Henry, Player, WhiteWolf};
+use crate::{
+    ai::{Action, ActionRequest},
+    assets::GameAssets,
+    combat::{DamageMessage, Dead, Health, LerpAttack},
+    console::Console,
+    maps::{in_arena, tile_index, LerpMove, RegionMap, TilePosition, TileType, ARENA_GATES},
+    status::{Effect, StatusEffects},
+    GameElement, TimeStepResource,
+};
+use bevy::prelude::*;
+
+/// Health fractions at which the fight moves on to its next phase
+const SUMMON_THRESHOLD: f32 = 0.66;
+const HOWL_THRESHOLD: f32 = 0.4;
+const ENRAGE_THRESHOLD: f32 = 0.2;
+
+/// Only charges at things lined up within this many tiles
+const CHARGE_RANGE: i32 = 8;
+/// Too close to bother charging; she just bites
+const CHARGE_MIN_RANGE: i32 = 3;
+const CHARGE_COOLDOWN: u32 = 150;
+/// A charge that misses leaves her dazed, which is your chance
+const DAZE_TICKS: u32 = 45;
+const PUPS: usize = 3;
+const HOWL_RADIUS: i32 = 4;
+const HOWL_COOLDOWN: u32 = 240;
+const STUN_TICKS: u32 = 45;
+/// Everything comes around this many times as fast once she's enraged
+const ENRAGE_HASTE: u32 = 3;
+
+const BAR_WIDTH: f32 = 300.0;
+const BAR_HEIGHT: f32 = 10.0;
+const BAR_Y: f32 = 768.0 / 2.0 - 40.0;
+
+/// The fight gets nastier as her health runs down
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum BossPhase {
+    Stalk,
+    Summon,
+    Howl,
+    Enrage,
+}
+
+impl BossPhase {
+    fn for_health(health: &Health) -> Self {
+        let left = health.current as f32 / health.max.max(1) as f32;
+        if left <= ENRAGE_THRESHOLD {
+            BossPhase::Enrage
+        } else if left <= HOWL_THRESHOLD {
+            BossPhase::Howl
+        } else if left <= SUMMON_THRESHOLD {
+            BossPhase::Summon
+        } else {
+            BossPhase::Stalk
+        }
+    }
+}
+
+/// A run in a straight line, a couple of tiles per leap
+struct Charge {
+    direction: (i32, i32),
+    leaps: i32,
+}
+
+/// The White Wolf's side of the boss fight
+#[derive(Component)]
+pub struct Boss {
+    pub phase: BossPhase,
+    /// Set once the arena has been sealed
+    pub fighting: bool,
+    charge: Option<Charge>,
+    charge_cooldown: u32,
+    howl_cooldown: u32,
+}
+
+impl Default for Boss {
+    fn default() -> Self {
+        Self {
+            phase: BossPhase::Stalk,
+            fighting: false,
+            charge: None,
+            charge_cooldown: CHARGE_COOLDOWN,
+            howl_cooldown: 0,
+        }
+    }
+}
+
+impl Boss {
+    fn haste(&self) -> u32 {
+        if self.phase == BossPhase::Enrage {
+            ENRAGE_HASTE
+        } else {
+            1
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct BossHealthBar;
+
+#[derive(Component)]
+pub struct BossHealthFill;
+
+/// The direction to charge in, if the target is lined up and nothing's in the way
+fn charge_line(map: &RegionMap, from: (i32, i32), to: (i32, i32)) -> Option<(i32, i32)> {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    if (dx != 0 && dy != 0) || dx.abs() + dy.abs() < CHARGE_MIN_RANGE {
+        return None;
+    }
+    if dx.abs() + dy.abs() > CHARGE_RANGE {
+        return None;
+    }
+    let direction = (dx.signum(), dy.signum());
+    let clear = (1..dx.abs() + dy.abs())
+        .all(|i| map.can_player_enter(from.0 + direction.0 * i, from.1 + direction.1 * i));
+    clear.then_some(direction)
+}
+
+/// Phases, charges, pups and howls. Ordinary hunting is left to her brain.
+pub fn white_wolf_boss(
+    mut boss: Query<
+        (
+            Entity,
+            &TilePosition,
+            &Health,
+            &mut Boss,
+            &mut StatusEffects,
+            &mut TextureAtlasSprite,
+            Option<&LerpMove>,
+            Option<&LerpAttack>,
+        ),
+        (With<WhiteWolf>, Without<Dead>),
+    >,
+    mut victims: Query<
+        (Entity, &TilePosition, Option<&Player>, &mut StatusEffects),
+        (Or<(With<Player>, With<Henry>)>, Without<WhiteWolf>),
+    >,
+    map: Res<RegionMap>,
+    archetypes: Res<Archetypes>,
+    assets: Res<GameAssets>,
+    mut commands: Commands,
+    mut actions: EventWriter<ActionRequest>,
+    mut damage: EventWriter<DamageMessage>,
+    console: Res<Console>,
+    timer: Res<TimeStepResource>,
+) {
+    if !timer.timer.finished() {
+        return;
+    }
+    let player = victims
+        .iter()
+        .find(|(_, _, player, _)| player.is_some())
+        .map(|(entity, pos, _, _)| (entity, (pos.x, pos.y)));
+    let (player, player_pos) = if let Some(player) = player {
+        player
+    } else {
+        return;
+    };
+
+    for (entity, pos, health, mut boss, mut effects, mut sprite, moving, attacking) in
+        boss.iter_mut()
+    {
+        if !boss.fighting {
+            continue;
+        }
+        let haste = boss.haste();
+        boss.charge_cooldown = boss.charge_cooldown.saturating_sub(haste);
+        boss.howl_cooldown = boss.howl_cooldown.saturating_sub(haste);
+        let here = (pos.x, pos.y);
+
+        // Phases only ever move on, even if she heals
+        let phase = BossPhase::for_health(health);
+        if phase > boss.phase {
+            boss.phase = phase;
+            match phase {
+                BossPhase::Stalk => {}
+                BossPhase::Summon => {
+                    console.write("The White Wolf calls her pups to her side!", Color::ORANGE);
+                    let mut spots: Vec<(i32, i32)> = (-2..=2)
+                        .flat_map(|dy| (-2..=2).map(move |dx| (here.0 + dx, here.1 + dy)))
+                        .filter(|(x, y)| {
+                            (*x, *y) != here
+                                && (*x, *y) != player_pos
+                                && map.can_player_enter(*x, *y)
+                        })
+                        .collect();
+                    spots.sort_by_key(|(x, y)| (x - here.0).abs() + (y - here.1).abs());
+                    for (x, y) in spots.iter().take(PUPS) {
+                        spawn_actor("WeakWolf", *x, *y, &archetypes, &assets, &mut commands);
+                    }
+                }
+                BossPhase::Howl => {
+                    console.write("The White Wolf throws back her head...", Color::ORANGE);
+                    boss.howl_cooldown = 0;
+                }
+                BossPhase::Enrage => {
+                    console.write("The White Wolf is enraged!", Color::RED);
+                    sprite.color = Color::rgb(1.0, 0.5, 0.5);
+                    effects.apply(Effect::Hasted, u32::MAX);
+                }
+            }
+        }
+
+        if moving.is_some() || attacking.is_some() || !effects.can_act() {
+            continue;
+        }
+
+        // A howl that rattles everyone close by
+        if boss.phase >= BossPhase::Howl && boss.howl_cooldown == 0 {
+            let deafened: Vec<Entity> = victims
+                .iter()
+                .filter(|(_, victim_pos, _, _)| {
+                    (victim_pos.x - here.0).abs() + (victim_pos.y - here.1).abs() <= HOWL_RADIUS
+                })
+                .map(|(victim, _, _, _)| victim)
+                .collect();
+            if !deafened.is_empty() {
+                if deafened.contains(&player) {
+                    console.write(
+                        "The White Wolf's howl rattles your bones! You're stunned!",
+                        Color::ORANGE,
+                    );
+                } else {
+                    console.write("The White Wolf's howl leaves Henry reeling!", Color::ORANGE);
+                }
+                for victim in deafened {
+                    if let Ok((_, _, _, mut victim_effects)) = victims.get_mut(victim) {
+                        victim_effects.apply(Effect::Stunned, STUN_TICKS);
+                    }
+                }
+                boss.howl_cooldown = HOWL_COOLDOWN;
+                effects.apply(Effect::Stunned, STUN_TICKS / 2);
+                continue;
+            }
+        }
+
+        if let Some(charge) = &mut boss.charge {
+            let next = (here.0 + charge.direction.0, here.1 + charge.direction.1);
+            let landing = (next.0 + charge.direction.0, next.1 + charge.direction.1);
+            if next == player_pos {
+                actions.send(ActionRequest {
+                    entity,
+                    action: Action::WantsToAttack {
+                        from: here,
+                        to: next,
+                        target: player,
+                    },
+                    priority: 3,
+                });
+                // The run-up adds a second helping
+                damage.send(DamageMessage {
+                    from: Some(entity),
+                    to: player,
+                });
+                console.write("The White Wolf slams into you!", Color::RED);
+                boss.charge = None;
+            } else if charge.leaps == 0 || !map.can_player_enter(next.0, next.1) {
+                console.write(
+                    "The White Wolf skids to a halt, dazed. Now's your chance!",
+                    Color::YELLOW,
+                );
+                effects.apply(Effect::Stunned, DAZE_TICKS);
+                boss.charge = None;
+            } else {
+                charge.leaps -= 1;
+                // Land short rather than on top of you
+                let (to, jumping) = if landing != player_pos && map.can_jump(here, landing) {
+                    (landing, true)
+                } else {
+                    (next, false)
+                };
+                actions.send(ActionRequest {
+                    entity,
+                    action: Action::Move {
+                        from: here,
+                        to,
+                        jumping,
+                    },
+                    priority: 3,
+                });
+            }
+            continue;
+        }
+
+        if boss.charge_cooldown == 0 {
+            if let Some(direction) = charge_line(&map, here, player_pos) {
+                console.write("The White Wolf lowers her head and charges!", Color::ORANGE);
+                let distance = (player_pos.0 - here.0).abs() + (player_pos.1 - here.1).abs();
+                boss.charge = Some(Charge {
+                    direction,
+                    // Enough to reach you, and then some if you dodge
+                    leaps: distance / 2 + 2,
+                });
+                boss.charge_cooldown = CHARGE_COOLDOWN;
+                // Pawing the ground gives you a moment's warning
+                actions.send(ActionRequest {
+                    entity,
+                    action: Action::Bump {
+                        from: here,
+                        toward: (here.0 + direction.0, here.1 + direction.1),
+                    },
+                    priority: 3,
+                });
+            }
+        }
+    }
+}
+
+fn set_gates(map: &mut RegionMap, meshes: &mut Assets<Mesh>, gate: TileType) {
+    for (x, y) in ARENA_GATES {
+        map.features[tile_index(x, y)] = gate;
+    }
+    map.rebuild_features(meshes);
+}
+
+/// Walls of fire seal the den once you walk in, and die down when she does
+pub fn arena_lock(
+    mut bosses: Query<(&mut Boss, Option<&Dead>)>,
+    player: Query<&TilePosition, With<Player>>,
+    bars: Query<Entity, With<BossHealthBar>>,
+    mut map: ResMut<RegionMap>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    assets: Res<GameAssets>,
+    mut commands: Commands,
+    console: Res<Console>,
+) {
+    for (mut boss, dead) in bosses.iter_mut() {
+        if dead.is_some() {
+            if boss.fighting {
+                boss.fighting = false;
+                set_gates(&mut map, &mut meshes, TileType::None);
+                bars.iter().for_each(|bar| commands.entity(bar).despawn());
+                console.write(
+                    "The White Wolf falls, and the flames die down with her.",
+                    Color::YELLOW,
+                );
+            }
+            continue;
+        }
+        let entered = player.iter().any(|pos| in_arena(pos.x, pos.y));
+        if boss.fighting || !entered {
+            continue;
+        }
+        boss.fighting = true;
+        set_gates(&mut map, &mut meshes, TileType::Fire);
+        spawn_health_bar(&mut commands, &assets);
+        console.write(
+            "Flames roar up behind you! There's no way out but through the White Wolf.",
+            Color::ORANGE,
+        );
+    }
+}
+
+fn spawn_health_bar(commands: &mut Commands, assets: &GameAssets) {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(0.1, 0.1, 0.1, 0.8),
+                custom_size: Some(Vec2::new(BAR_WIDTH + 4.0, BAR_HEIGHT + 4.0)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, BAR_Y, 5.0),
+            ..default()
+        })
+        .insert(BossHealthBar)
+        .insert(GameElement);
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgb(0.8, 0.1, 0.1),
+                custom_size: Some(Vec2::new(BAR_WIDTH, BAR_HEIGHT)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, BAR_Y, 5.1),
+            ..default()
+        })
+        .insert(BossHealthFill)
+        .insert(BossHealthBar)
+        .insert(GameElement);
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                "The White Wolf",
+                TextStyle {
+                    font: assets.font.clone(),
+                    font_size: 16.0,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    horizontal: HorizontalAlign::Center,
+                    vertical: VerticalAlign::Center,
+                },
+            ),
+            transform: Transform::from_xyz(0.0, BAR_Y + 16.0, 5.0),
+            ..default()
+        })
+        .insert(BossHealthBar)
+        .insert(GameElement);
+}
+
+/// Shrinks from the right as she takes damage
+pub fn update_boss_health_bar(
+    bosses: Query<(&Health, &Boss)>,
+    mut fill: Query<(&mut Sprite, &mut Transform), With<BossHealthFill>>,
+) {
+    for (health, boss) in bosses.iter() {
+        let left = health.current.max(0) as f32 / health.max.max(1) as f32;
+        for (mut sprite, mut transform) in fill.iter_mut() {
+            let width = BAR_WIDTH * left;
+            sprite.custom_size = Some(Vec2::new(width, BAR_HEIGHT));
+            transform.translation.x = (width - BAR_WIDTH) / 2.0;
+            if boss.phase == BossPhase::Enrage {
+                sprite.color = Color::rgb(1.0, 0.3, 0.0);
+            }
+        }
+    }
+}
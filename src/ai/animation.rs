@@ -1,6 +1,229 @@
-use bevy::prelude::*;
+use super::Facing;
+use crate::{
+    combat::{DamageMessage, Dead, LerpAttack},
+    maps::LerpMove,
+    status::{Effect, StatusEffects},
+    TimeStepResource,
+};
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use serde::Deserialize;
 
-#[derive(Component)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Deserialize)]
+pub enum AnimationState {
+    Idle,
+    Walk,
+    Jump,
+    Attack,
+    Hurt,
+    Unconscious,
+    Death,
+}
+
+impl AnimationState {
+    /// What to play instead, for actors without a clip of their own
+    fn fallback(&self) -> Option<AnimationState> {
+        match self {
+            AnimationState::Jump | AnimationState::Attack => Some(AnimationState::Walk),
+            AnimationState::Hurt | AnimationState::Unconscious => Some(AnimationState::Idle),
+            AnimationState::Idle | AnimationState::Walk | AnimationState::Death => None,
+        }
+    }
+}
+
+fn default_ticks() -> u32 {
+    1
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Clip {
+    /// Frames facing left, right, up and down. A single list serves every facing.
+    pub frames: Vec<Vec<usize>>,
+    #[serde(default = "default_ticks")]
+    pub ticks_per_frame: u32,
+    #[serde(default)]
+    pub looping: bool,
+}
+
+impl Clip {
+    pub fn new(frames: Vec<Vec<usize>>) -> Self {
+        Self {
+            frames,
+            ticks_per_frame: 1,
+            looping: false,
+        }
+    }
+
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    pub fn ticks_per_frame(mut self, ticks: u32) -> Self {
+        self.ticks_per_frame = ticks;
+        self
+    }
+
+    fn frames(&self, facing: Facing) -> &[usize] {
+        if self.frames.len() == 1 {
+            &self.frames[0]
+        } else {
+            &self.frames[facing.index()]
+        }
+    }
+}
+
+#[derive(Component, Default)]
 pub struct AnimationSet {
-    pub animations: Vec<Vec<usize>>, // vector for all directions, containing each frame
+    pub clips: HashMap<AnimationState, Clip>,
+}
+
+impl AnimationSet {
+    pub fn with(mut self, state: AnimationState, clip: Clip) -> Self {
+        self.clips.insert(state, clip);
+        self
+    }
+
+    fn clip(&self, state: AnimationState) -> Option<&Clip> {
+        self.clips
+            .get(&state)
+            .or_else(|| state.fallback().and_then(|fallback| self.clip(fallback)))
+    }
+
+    /// Standing around without an idle clip holds the first walking frame
+    fn frame(&self, state: AnimationState, facing: Facing, frame: usize) -> Option<usize> {
+        if let Some(clip) = self.clip(state) {
+            clip.frames(facing).get(frame).copied()
+        } else if state == AnimationState::Idle {
+            self.clips
+                .get(&AnimationState::Walk)
+                .and_then(|walk| walk.frames(facing).first().copied())
+        } else {
+            None
+        }
+    }
+}
+
+/// Where an actor is up to in its current clip
+#[derive(Component)]
+pub struct Animator {
+    pub state: AnimationState,
+    pub facing: Facing,
+    frame: usize,
+    ticks: u32,
+    finished: bool,
+}
+
+impl Animator {
+    pub fn new(facing: Facing) -> Self {
+        Self {
+            state: AnimationState::Idle,
+            facing,
+            frame: 0,
+            ticks: 0,
+            finished: false,
+        }
+    }
+
+    /// Switches clip, from the top. Returns false if it was already playing.
+    fn play(&mut self, state: AnimationState, restart: bool) -> bool {
+        if self.state == state && !restart {
+            return false;
+        }
+        self.state = state;
+        self.frame = 0;
+        self.ticks = 0;
+        self.finished = false;
+        true
+    }
+
+    fn advance(&mut self, clip: &Clip, facing: Facing) {
+        self.ticks += 1;
+        if self.ticks < clip.ticks_per_frame {
+            return;
+        }
+        self.ticks = 0;
+        let length = clip.frames(facing).len();
+        if self.frame + 1 < length {
+            self.frame += 1;
+        } else if clip.looping {
+            self.frame = 0;
+        } else {
+            self.finished = true;
+        }
+    }
+}
+
+/// Picks each actor's state from what it is doing, then steps through the clip
+pub fn animate(
+    mut actors: Query<(
+        Entity,
+        &AnimationSet,
+        &mut Animator,
+        &mut TextureAtlasSprite,
+        Option<&LerpMove>,
+        Option<&LerpAttack>,
+        Option<&StatusEffects>,
+        Option<&Dead>,
+    )>,
+    mut damage: EventReader<DamageMessage>,
+    timer: Res<TimeStepResource>,
+) {
+    let hurt: HashSet<Entity> = damage.iter().map(|damage| damage.to).collect();
+    for (entity, set, mut animator, mut sprite, moving, attacking, effects, dead) in
+        actors.iter_mut()
+    {
+        let has = |effect| effects.is_some_and(|effects| effects.has(effect));
+        let (state, heading) = if dead.is_some() {
+            (AnimationState::Death, None)
+        } else if has(Effect::Unconscious) {
+            (AnimationState::Unconscious, None)
+        } else if let Some(attack) = attacking {
+            (AnimationState::Attack, Some((attack.start, attack.end)))
+        } else if let Some(lerp) = moving {
+            if lerp.jumping {
+                (AnimationState::Jump, Some((lerp.start, lerp.end)))
+            } else {
+                (AnimationState::Walk, Some((lerp.start, lerp.end)))
+            }
+        } else if has(Effect::Stunned)
+            || hurt.contains(&entity)
+            || (animator.state == AnimationState::Hurt && !animator.finished)
+        {
+            (AnimationState::Hurt, None)
+        } else {
+            (AnimationState::Idle, None)
+        };
+        if let Some((from, to)) = heading {
+            if from != to {
+                animator.facing = Facing::from_delta(to.0 - from.0, to.1 - from.1);
+            }
+        }
+        // Taking another hit starts the flinch over
+        let restart = state == AnimationState::Hurt && hurt.contains(&entity);
+        let started = animator.play(state, restart);
+
+        let facing = animator.facing;
+        if timer.timer.finished() && !started {
+            match set.clip(state) {
+                Some(clip) => animator.advance(clip, facing),
+                None => animator.finished = true,
+            }
+        }
+        if let Some(frame) = set.frame(state, facing, animator.frame) {
+            sprite.index = frame;
+        }
+    }
+}
+
+/// Without a death clip, the dead just keel over
+pub fn topple_the_dead(mut dead: Query<(&mut Transform, Option<&AnimationSet>), Added<Dead>>) {
+    for (mut transform, set) in dead.iter_mut() {
+        let has_clip = set.is_some_and(|set| set.clips.contains_key(&AnimationState::Death));
+        if !has_clip {
+            transform.rotation = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2);
+        }
+    }
 }
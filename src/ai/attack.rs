@@ -1,41 +1,32 @@
-use super::ActionRequest;
+use super::{ActionRequest, Brain, Intent};
 use crate::{
-    combat::{Dead, LerpAttack, Unconscious},
+    combat::{Dead, LerpAttack},
     maps::{LerpMove, TilePosition},
+    status::{is_unconscious, StatusEffects},
     TimeStepResource,
 };
 use bevy::prelude::*;
-use bracket_pathfinding::prelude::{DistanceAlg, Point};
 
-pub fn attacks<TYPE, TARGET>(
+/// Takes a swing at whatever the brain decided to attack
+pub fn attacks(
     me: Query<
-        (Entity, &TilePosition),
-        (
-            With<TYPE>,
-            (
-                Without<Unconscious>,
-                Without<LerpMove>,
-                Without<LerpAttack>,
-                Without<Dead>,
-            ),
-        ),
+        (Entity, &TilePosition, &Brain),
+        (Without<LerpMove>, Without<LerpAttack>, Without<Dead>),
     >,
-    them: Query<(Entity, &TilePosition), (With<TARGET>, Without<Unconscious>, Without<Dead>)>,
+    them: Query<(&TilePosition, Option<&StatusEffects>), Without<Dead>>,
     mut actions: EventWriter<ActionRequest>,
     timer: Res<TimeStepResource>,
-) where
-    TYPE: Component,
-    TARGET: Component,
-{
+) {
     if !timer.timer.finished() {
         return;
     }
-    for (entity, my_pos) in me.iter() {
-        let my_point = Point::new(my_pos.x, my_pos.y);
-        for (target, their_pos) in them.iter() {
-            let their_point = Point::new(their_pos.x, their_pos.y);
-            let distance = DistanceAlg::Pythagoras.distance2d(my_point, their_point);
-            if distance < 1.5 {
+    for (entity, my_pos, brain) in me.iter() {
+        if let Intent::Attack(target) = brain.intent {
+            if let Ok((their_pos, effects)) = them.get(target) {
+                // No kicking someone while they're down
+                if is_unconscious(effects) {
+                    continue;
+                }
                 actions.send(ActionRequest {
                     entity,
                     action: super::Action::WantsToAttack {
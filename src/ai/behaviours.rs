@@ -0,0 +1,48 @@
+use super::{Ambushes, Memory, PackMember, ShootsWebs, Weaves};
+use crate::stealth::Awareness;
+use bevy::{ecs::system::EntityCommands, prelude::*};
+use serde::Deserialize;
+
+/// AI behaviours an actor archetype can opt into
+#[derive(Deserialize, Clone, Copy)]
+pub enum Behaviour {
+    Wander,
+    Flee,
+    Hunt,
+    Follow,
+    Pack,
+    Weave,
+    ShootWebs,
+    Ambush,
+}
+
+impl Behaviour {
+    pub fn insert_marker(&self, entity: &mut EntityCommands) {
+        match self {
+            Behaviour::Wander => entity.insert(Wanders),
+            Behaviour::Flee => entity.insert(Flees),
+            Behaviour::Hunt => entity
+                .insert(Hunts)
+                .insert(Memory::default())
+                .insert(Awareness::default()),
+            Behaviour::Follow => entity.insert(Follows),
+            Behaviour::Pack => entity.insert(PackMember::default()),
+            Behaviour::Weave => entity.insert(Weaves::default()),
+            Behaviour::ShootWebs => entity.insert(ShootsWebs::default()),
+            Behaviour::Ambush => entity.insert(Ambushes),
+        };
+    }
+}
+
+#[derive(Component)]
+pub struct Wanders;
+
+#[derive(Component)]
+pub struct Flees;
+
+#[derive(Component)]
+pub struct Hunts;
+
+/// Tags along behind the player
+#[derive(Component)]
+pub struct Follows;
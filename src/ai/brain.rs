@@ -0,0 +1,374 @@
+use super::{
+    in_path_of, Action, ActionRejected, CompanionOrder, Flees, Follows, Hunts, Memory, Recruited,
+    RejectReason, Wanders,
+};
+use crate::{
+    actors::Player,
+    combat::{Dead, Health},
+    factions::{Faction, Relationships},
+    fov::FieldOfView,
+    maps::{coop_center, in_coop, TilePosition},
+    status::{can_act, is_unconscious, StatusEffects},
+    stealth::{Awareness, Stealth},
+    TimeStepResource,
+};
+use bevy::prelude::*;
+use bracket_pathfinding::prelude::{DistanceAlg, Point};
+
+/// Everyone still standing. Anyone out cold is nobody's concern, so check before using one.
+type Others<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static TilePosition,
+        &'static Faction,
+        Option<&'static StatusEffects>,
+    ),
+    Without<Dead>,
+>;
+
+/// The one thing an actor has decided to do this tick
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Intent {
+    Idle,
+    Wander,
+    Flee,
+    Chase(Entity),
+    Attack(Entity),
+    Follow(Entity),
+    Flock(Entity),
+    GoTo((i32, i32)),
+    Investigate((i32, i32)),
+    Search((i32, i32)),
+}
+
+impl Intent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Intent::Idle => "Idle",
+            Intent::Wander => "Wander",
+            Intent::Flee => "Flee",
+            Intent::Chase(_) => "Chase",
+            Intent::Attack(_) => "Attack",
+            Intent::Follow(_) => "Follow",
+            Intent::Flock(_) => "Flock",
+            Intent::GoTo(_) => "GoTo",
+            Intent::Investigate(_) => "Investigate",
+            Intent::Search(_) => "Search",
+        }
+    }
+}
+
+/// Scores every option an actor has, and keeps the best one as its intent.
+/// The scores are kept around so they can be inspected.
+#[derive(Component, Debug)]
+pub struct Brain {
+    pub intent: Intent,
+    pub score: f32,
+    pub scores: Vec<(Intent, f32)>,
+    /// Set when the last action this brain asked for was turned down
+    pub rejected: Option<RejectReason>,
+}
+
+impl Default for Brain {
+    fn default() -> Self {
+        Self {
+            intent: Intent::Idle,
+            score: 0.0,
+            scores: Vec::new(),
+            rejected: None,
+        }
+    }
+}
+
+const IDLE_SCORE: f32 = 0.05;
+/// Makes an actor think twice about something that just didn't work out
+const REJECTED_PENALTY: f32 = 0.3;
+const WANDER_SCORE: f32 = 0.1;
+const ATTACK_SCORE: f32 = 0.9;
+const ATTACK_RANGE: f32 = 1.5;
+const IN_THE_WAY_SCORE: f32 = 0.5;
+const INVESTIGATE_SCORE: f32 = 0.5;
+const SEARCH_SCORE: f32 = 0.3;
+/// Followers close the gap once they're this far behind
+pub const FOLLOW_DISTANCE: f32 = 2.5;
+/// Recruited chickens hurry back once they're this far from the flock's leader
+const FLOCK_DISTANCE: f32 = 4.0;
+const FLOCK_SCORE: f32 = 0.15;
+/// Guards only chase things that come this close to whatever they're guarding
+const GUARD_RADIUS: f32 = 4.0;
+const ORDERED_SCORE: f32 = 1.0;
+/// Retreating companions keep running until threats are this far away
+const RETREAT_DISTANCE: f32 = 6.0;
+/// Herders circle round this far behind a chicken, on the side away from the coop
+const HERD_DISTANCE: f32 = 2.0;
+
+fn distance(a: &TilePosition, b: &TilePosition) -> f32 {
+    DistanceAlg::Pythagoras.distance2d(Point::new(a.x, a.y), Point::new(b.x, b.y))
+}
+
+/// How close is it, from 1.0 (on top of you) down to 0.0 (edge of vision)
+fn closeness(distance: f32, range: i32) -> f32 {
+    f32::max(0.0, 1.0 - distance / (range as f32 + 1.0))
+}
+
+pub fn think(
+    mut brains: Query<(
+        Entity,
+        &mut Brain,
+        &TilePosition,
+        &Faction,
+        &Health,
+        Option<&FieldOfView>,
+        (
+            Option<&Wanders>,
+            Option<&Flees>,
+            Option<&Hunts>,
+            Option<&Follows>,
+            Option<&CompanionOrder>,
+            Option<&Memory>,
+            Option<&Awareness>,
+            Option<&Recruited>,
+        ),
+    )>,
+    others: Others,
+    knocked_out: Query<(Option<&StatusEffects>, Option<&Dead>)>,
+    stealthy: Query<(), With<Stealth>>,
+    player: Query<(Entity, &TilePosition, &Player)>,
+    relationships: Res<Relationships>,
+    timer: Res<TimeStepResource>,
+) {
+    if !timer.timer.finished() {
+        return;
+    }
+    for (
+        entity,
+        mut brain,
+        pos,
+        faction,
+        health,
+        fov,
+        (wanders, flees, hunts, follows, order, memory, awareness, recruited),
+    ) in brains.iter_mut()
+    {
+        let mut scores = vec![(Intent::Idle, IDLE_SCORE)];
+        if knocked_out
+            .get(entity)
+            .is_ok_and(|(effects, dead)| dead.is_some() || !can_act(effects))
+        {
+            brain.intent = Intent::Idle;
+            brain.score = IDLE_SCORE;
+            brain.scores = scores;
+            continue;
+        }
+
+        // Being hurt makes you braver about running away, and less keen on a fight
+        let health = health.current.max(0) as f32 / health.max.max(1) as f32;
+        let can_see = |other: &TilePosition| {
+            fov.is_some_and(|fov| fov.fov_set.contains(&Point::new(other.x, other.y)))
+        };
+        let range = fov.map_or(1, |fov| fov.range);
+
+        if wanders.is_some() {
+            scores.push((Intent::Wander, WANDER_SCORE));
+        }
+
+        let mut nearest_threat: Option<f32> = None;
+        let mut nearest_prey: Option<(Entity, f32)> = None;
+        let mut adjacent_prey: Option<Entity> = None;
+        for (other, other_pos, other_faction, effects) in others.iter() {
+            if other == entity || is_unconscious(effects) {
+                continue;
+            }
+            let d = distance(pos, other_pos);
+            if relationships.is_afraid(*faction, *other_faction) && can_see(other_pos) {
+                nearest_threat = Some(nearest_threat.map_or(d, |n| f32::min(n, d)));
+            }
+            // You can't go after something you haven't spotted yet
+            let noticed = stealthy.get(other).is_err()
+                || awareness.map_or(true, |awareness| awareness.noticed(other));
+            if relationships.is_hostile(*faction, *other_faction) && noticed {
+                if d < ATTACK_RANGE && adjacent_prey.is_none() {
+                    adjacent_prey = Some(other);
+                }
+                if can_see(other_pos) && nearest_prey.map_or(true, |(_, n)| d < n) {
+                    nearest_prey = Some((other, d));
+                }
+            }
+        }
+
+        if let Some(target) = adjacent_prey {
+            scores.push((Intent::Attack(target), ATTACK_SCORE));
+        }
+        if let (Some(d), Some(_)) = (nearest_threat, flees) {
+            let score = closeness(d, range) + 0.5 * (1.0 - health);
+            scores.push((Intent::Flee, f32::min(1.0, score)));
+        }
+        if let (Some((target, d)), Some(_)) = (nearest_prey, hunts) {
+            let score = 0.4 + 0.3 * health + 0.2 * closeness(d, range);
+            scores.push((Intent::Chase(target), score));
+        } else if let (Some(memory), Some(_)) = (memory, hunts) {
+            // Out of sight isn't out of mind
+            if let Some((_, sighting)) = memory.freshest() {
+                scores.push((Intent::Investigate(sighting.position), INVESTIGATE_SCORE));
+            } else if let Some(search) = memory.search {
+                scores.push((Intent::Search(search.center), SEARCH_SCORE));
+            }
+        }
+        if follows.is_some() {
+            if let Ok((leader, leader_pos, player)) = player.get_single() {
+                // The further behind you fall, the more it matters
+                let d = distance(pos, leader_pos);
+                if d > FOLLOW_DISTANCE {
+                    scores.push((Intent::Follow(leader), f32::clamp(d / 10.0, 0.2, 1.0)));
+                } else if in_path_of((leader_pos.x, leader_pos.y), player.facing, (pos.x, pos.y)) {
+                    scores.push((Intent::Follow(leader), IN_THE_WAY_SCORE));
+                }
+            }
+        }
+
+        // A flock scatters as soon as anything scary shows up
+        if let (Some(_), None) = (recruited, nearest_threat) {
+            if let Ok((leader, leader_pos, _)) = player.get_single() {
+                let d = distance(pos, leader_pos);
+                let score = if d > FLOCK_DISTANCE {
+                    f32::clamp(d / 10.0, 0.3, 0.8)
+                } else {
+                    FLOCK_SCORE
+                };
+                scores.push((Intent::Flock(leader), score));
+            }
+        }
+
+        if let Some(order) = order {
+            obey(*order, pos, faction, &mut scores, &others, &relationships);
+        }
+
+        if brain.rejected.take().is_some() {
+            let failed = brain.intent;
+            scores
+                .iter_mut()
+                .filter(|(intent, _)| *intent == failed)
+                .for_each(|(_, score)| *score -= REJECTED_PENALTY);
+        }
+
+        // First one listed wins a tie
+        let (intent, score) =
+            scores
+                .iter()
+                .copied()
+                .fold((Intent::Idle, f32::MIN), |best, option| {
+                    if option.1 > best.1 {
+                        option
+                    } else {
+                        best
+                    }
+                });
+        brain.intent = intent;
+        brain.score = score;
+        brain.scores = scores;
+    }
+}
+
+/// Companions weigh up their options as usual, then their orders trump them
+fn obey(
+    order: CompanionOrder,
+    pos: &TilePosition,
+    faction: &Faction,
+    scores: &mut Vec<(Intent, f32)>,
+    others: &Others,
+    relationships: &Relationships,
+) {
+    match order {
+        CompanionOrder::Follow => {}
+        CompanionOrder::Stay => {
+            scores.retain(|(intent, _)| !matches!(intent, Intent::Follow(_) | Intent::Chase(_)));
+        }
+        CompanionOrder::Attack(target) => {
+            let target = others
+                .get(target)
+                .ok()
+                .filter(|(_, _, _, effects)| !is_unconscious(*effects));
+            if let Some((target, target_pos, _, _)) = target {
+                if distance(pos, target_pos) < ATTACK_RANGE {
+                    scores.push((Intent::Attack(target), ORDERED_SCORE));
+                } else {
+                    scores.push((Intent::Chase(target), ORDERED_SCORE));
+                }
+            }
+        }
+        CompanionOrder::Fetch(spot) => {
+            scores.push((Intent::GoTo(spot), ORDERED_SCORE));
+        }
+        CompanionOrder::Guard(spot) => {
+            let post = TilePosition {
+                x: spot.0,
+                y: spot.1,
+            };
+            scores.retain(|(intent, _)| match intent {
+                Intent::Follow(_) => false,
+                Intent::Chase(target) => others
+                    .get(*target)
+                    .is_ok_and(|(_, target_pos, _, _)| distance(&post, target_pos) <= GUARD_RADIUS),
+                _ => true,
+            });
+            let chasing = scores.iter().any(|(i, _)| matches!(i, Intent::Chase(_)));
+            if (pos.x, pos.y) != spot && !chasing {
+                scores.push((Intent::GoTo(spot), 0.5));
+            }
+        }
+        CompanionOrder::Retreat => {
+            scores.retain(|(intent, _)| !matches!(intent, Intent::Attack(_) | Intent::Chase(_)));
+            let threatened = others.iter().any(|(_, other_pos, other_faction, effects)| {
+                !is_unconscious(effects)
+                    && relationships.is_hostile(*faction, *other_faction)
+                    && distance(pos, other_pos) < RETREAT_DISTANCE
+            });
+            if threatened {
+                scores.push((Intent::Flee, ORDERED_SCORE));
+            }
+        }
+        CompanionOrder::Herd => {
+            scores.retain(|(intent, _)| !matches!(intent, Intent::Follow(_) | Intent::Chase(_)));
+            // Get behind the nearest stray, so running away from you takes it home
+            let stray = others
+                .iter()
+                .filter(|(_, other_pos, other_faction, _)| {
+                    **other_faction == Faction::Critters && !in_coop(other_pos.x, other_pos.y)
+                })
+                .map(|(_, other_pos, _, _)| (distance(pos, other_pos), other_pos))
+                .min_by(|a, b| a.0.total_cmp(&b.0));
+            if let Some((_, stray)) = stray {
+                let center = coop_center();
+                let away = Vec2::new((stray.x - center.0) as f32, (stray.y - center.1) as f32)
+                    .normalize_or_zero()
+                    * HERD_DISTANCE;
+                let spot = (
+                    stray.x + away.x.round() as i32,
+                    stray.y + away.y.round() as i32,
+                );
+                if (pos.x, pos.y) != spot {
+                    scores.push((Intent::GoTo(spot), ORDERED_SCORE));
+                }
+            }
+        }
+    }
+}
+
+pub fn note_rejections(mut rejections: EventReader<ActionRejected>, mut brains: Query<&mut Brain>) {
+    for rejection in rejections.iter() {
+        // Bumps are just for show, and being busy or out of turn sorts itself out
+        if matches!(rejection.action, Action::Bump { .. })
+            || matches!(
+                rejection.reason,
+                RejectReason::Busy | RejectReason::OutOfTurn
+            )
+        {
+            continue;
+        }
+        if let Ok(mut brain) = brains.get_mut(rejection.entity) {
+            brain.rejected = Some(rejection.reason);
+        }
+    }
+}
@@ -15,4 +15,31 @@ impl Facing {
             Facing::Down => 3,
         }
     }
+
+    pub fn delta(&self) -> (i32, i32) {
+        match self {
+            Facing::Left => (-1, 0),
+            Facing::Right => (1, 0),
+            Facing::Up => (0, -1),
+            Facing::Down => (0, 1),
+        }
+    }
+
+    pub fn from_delta(dx: i32, dy: i32) -> Self {
+        if dx < 0 {
+            Facing::Left
+        } else if dx > 0 {
+            Facing::Right
+        } else if dy < 0 {
+            Facing::Up
+        } else {
+            Facing::Down
+        }
+    }
+}
+
+/// Is `other` standing in the next couple of tiles someone at `pos` is walking toward?
+pub fn in_path_of(pos: (i32, i32), facing: Facing, other: (i32, i32)) -> bool {
+    let (dx, dy) = facing.delta();
+    (1..=2).any(|n| (pos.0 + dx * n, pos.1 + dy * n) == other)
 }
@@ -1,5 +1,6 @@
 use crate::{
-    combat::{Dead, LerpAttack, Unconscious},
+    combat::{Dead, LerpAttack},
+    factions::{Faction, Relationships},
     fov::FieldOfView,
     maps::{tile_index, LerpMove, RegionMap, TilePosition, NUM_TILES_X, NUM_TILES_Y},
     TimeStepResource,
@@ -7,34 +8,42 @@ use crate::{
 use bevy::prelude::*;
 use bracket_pathfinding::prelude::{DijkstraMap, Point};
 
-use super::{Action, ActionRequest};
+use super::{Action, ActionRequest, Brain, CompanionOrder, Intent, Routed};
 
-pub fn flee_from<TYPE, SCARY>(
+/// Runs from everything scary in sight, once the brain decides it's time to go
+pub fn flee_from(
     ai_query: Query<
-        (Entity, &TilePosition, &FieldOfView),
         (
-            With<TYPE>,
-            Without<LerpMove>,
-            Without<LerpAttack>,
-            Without<Unconscious>,
-            Without<Dead>,
+            Entity,
+            &TilePosition,
+            &FieldOfView,
+            &Faction,
+            &Brain,
+            Option<&CompanionOrder>,
+            Option<&Routed>,
         ),
+        (Without<LerpMove>, Without<LerpAttack>, Without<Dead>),
     >,
-    scary_query: Query<&TilePosition, (With<SCARY>, Without<Dead>)>,
+    scary_query: Query<(&TilePosition, &Faction), Without<Dead>>,
+    relationships: Res<Relationships>,
     map: Res<RegionMap>,
     mut actions: EventWriter<ActionRequest>,
     timer: Res<TimeStepResource>,
-) where
-    SCARY: Component,
-    TYPE: Component,
-{
+) {
     if !timer.timer.finished() {
         return;
     }
-    for (entity, pos, fov) in ai_query.iter() {
-        if !fov.fov_set.is_empty() {
+    for (entity, pos, fov, faction, brain, order, routed) in ai_query.iter() {
+        // A routed pack runs from the very things it was hunting
+        let retreating = order == Some(&CompanionOrder::Retreat) || routed.is_some();
+        if brain.intent == Intent::Flee && !fov.fov_set.is_empty() {
             let mut starts = Vec::new();
-            for epos in scary_query.iter() {
+            for (epos, their_faction) in scary_query.iter() {
+                let scary = relationships.is_afraid(*faction, *their_faction)
+                    || (retreating && relationships.is_hostile(*faction, *their_faction));
+                if !scary {
+                    continue;
+                }
                 let pt = Point::new(epos.x, epos.y);
                 if fov.fov_set.contains(&pt) {
                     starts.push(tile_index(pt.x, pt.y));
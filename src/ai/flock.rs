@@ -0,0 +1,184 @@
+use super::{in_path_of, Action, ActionRequest, Brain, Facing, Intent};
+use crate::{
+    actors::Player,
+    combat::{Dead, Health, LerpAttack},
+    console::Console,
+    maps::{tile_index, LerpMove, Navigator, Occupancy, RegionMap, TilePosition, TileType},
+    random::Rng,
+    TimeStepResource,
+};
+use bevy::prelude::*;
+use bracket_pathfinding::prelude::{DistanceAlg, Point};
+
+/// Flock members like to keep about this far from the Mega Chicken
+const FLOCK_RADIUS: f32 = 2.0;
+/// Beyond this, stragglers stop milling about and path straight back
+const STRAGGLER_DISTANCE: f32 = 5.0;
+const SEPARATION_WEIGHT: f32 = 1.0;
+const IN_THE_WAY_COST: f32 = 2.0;
+const ALIGNMENT_BONUS: f32 = 0.25;
+/// A little laziness, so the flock settles down instead of jostling forever
+const STAY_BONUS: f32 = 0.1;
+/// Each chicken in the flock has a one in this many chance per tick of scratching up grain
+const FORAGE_CHANCE: i32 = 2000;
+/// The player gets a point of max health for every two chickens, up to this many
+const MAX_FLOCK_BONUS: i32 = 3;
+
+/// A chicken that has thrown in its lot with the Mega Chicken
+#[derive(Component)]
+pub struct Recruited;
+
+/// How much max health the flock is currently lending the player
+#[derive(Component, Default)]
+pub struct FlockBonus(i32);
+
+fn distance(a: (i32, i32), b: (i32, i32)) -> f32 {
+    DistanceAlg::Pythagoras.distance2d(Point::new(a.0, a.1), Point::new(b.0, b.1))
+}
+
+/// Boids on a grid: keep near the leader, keep apart from each other, stay out of the way
+fn boid_cost(
+    tile: (i32, i32),
+    here: (i32, i32),
+    leader: (i32, i32),
+    facing: Option<Facing>,
+    flockmates: &[(i32, i32)],
+) -> f32 {
+    let cohesion = (distance(tile, leader) - FLOCK_RADIUS).abs();
+    let crowding = flockmates
+        .iter()
+        .filter(|mate| (mate.0 - tile.0).abs() <= 1 && (mate.1 - tile.1).abs() <= 1)
+        .count() as f32;
+    let mut cost = cohesion + crowding * SEPARATION_WEIGHT;
+    if let Some(facing) = facing {
+        if in_path_of(leader, facing, tile) {
+            cost += IN_THE_WAY_COST;
+        }
+        if (tile.0 - here.0, tile.1 - here.1) == facing.delta() {
+            cost -= ALIGNMENT_BONUS;
+        }
+    }
+    if tile == here {
+        cost -= STAY_BONUS;
+    }
+    cost
+}
+
+pub fn flock(
+    members: Query<
+        (Entity, &TilePosition, &Brain),
+        (
+            With<Recruited>,
+            Without<LerpMove>,
+            Without<LerpAttack>,
+            Without<Dead>,
+        ),
+    >,
+    flockmates: Query<(Entity, &TilePosition), (With<Recruited>, Without<Dead>)>,
+    leaders: Query<(&TilePosition, Option<&Player>)>,
+    map: Res<RegionMap>,
+    occupancy: Res<Occupancy>,
+    mut actions: EventWriter<ActionRequest>,
+    timer: Res<TimeStepResource>,
+) {
+    if !timer.timer.finished() {
+        return;
+    }
+    for (entity, pos, brain) in members.iter() {
+        let leader = if let Intent::Flock(leader) = brain.intent {
+            leader
+        } else {
+            continue;
+        };
+        let (leader_pos, facing) = if let Ok((leader_pos, player)) = leaders.get(leader) {
+            (
+                (leader_pos.x, leader_pos.y),
+                player.map(|player| player.facing),
+            )
+        } else {
+            continue;
+        };
+        let here = (pos.x, pos.y);
+
+        let step = if distance(here, leader_pos) > STRAGGLER_DISTANCE {
+            Navigator::new(&map)
+                .avoiding(&occupancy, entity)
+                .path(here, leader_pos)
+                .and_then(|path| path.first().copied())
+        } else {
+            let others: Vec<(i32, i32)> = flockmates
+                .iter()
+                .filter(|(mate, _)| *mate != entity)
+                .map(|(_, mate_pos)| (mate_pos.x, mate_pos.y))
+                .collect();
+            let options = [(0, 0), (-1, 0), (1, 0), (0, -1), (0, 1)]
+                .iter()
+                .map(|(dx, dy)| (here.0 + dx, here.1 + dy))
+                .filter(|tile| {
+                    *tile == here
+                        || (map.can_player_enter(tile.0, tile.1)
+                            && !occupancy.is_blocked(tile.0, tile.1, entity))
+                });
+            options
+                .map(|tile| (tile, boid_cost(tile, here, leader_pos, facing, &others)))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(tile, _)| tile)
+        };
+
+        if let Some(to) = step {
+            if to != here {
+                actions.send(ActionRequest {
+                    entity,
+                    action: Action::Move {
+                        from: here,
+                        to,
+                        jumping: false,
+                    },
+                    priority: 1,
+                });
+            }
+        }
+    }
+}
+
+/// Chickens look after their own: the flock lends the player some toughness
+pub fn flock_buffs(
+    flock: Query<(), (With<Recruited>, Without<Dead>)>,
+    mut player: Query<(&mut Health, &mut FlockBonus), With<Player>>,
+) {
+    let bonus = i32::min(flock.iter().count() as i32 / 2, MAX_FLOCK_BONUS);
+    for (mut health, mut applied) in player.iter_mut() {
+        if applied.0 == bonus {
+            continue;
+        }
+        let change = bonus - applied.0;
+        health.max += change;
+        health.current = i32::min(health.current + change.max(0), health.max);
+        applied.0 = bonus;
+    }
+}
+
+/// ...and now and then scratches up some grain
+pub fn forage(
+    flock: Query<&TilePosition, (With<Recruited>, Without<Dead>, Without<LerpMove>)>,
+    mut map: ResMut<RegionMap>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    rng: Res<Rng>,
+    console: Res<Console>,
+    timer: Res<TimeStepResource>,
+) {
+    if !timer.timer.finished() {
+        return;
+    }
+    for pos in flock.iter() {
+        let idx = tile_index(pos.x, pos.y);
+        if map.features[idx] == TileType::None && rng.range(0, FORAGE_CHANCE) == 0 {
+            map.features[idx] = TileType::Grain;
+            map.rebuild_features(&mut meshes);
+            console.write(
+                "One of your chickens scratches up some grain.",
+                Color::YELLOW,
+            );
+        }
+    }
+}
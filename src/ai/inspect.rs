@@ -0,0 +1,96 @@
+use super::{Brain, PackMember};
+use crate::{assets::GameAssets, console::Console, maps::MapElement, GameElement};
+use bevy::{prelude::*, utils::HashSet};
+
+/// Toggled with F3: floats each actor's current intent and score above its head
+#[derive(Default)]
+pub struct BrainInspector {
+    pub enabled: bool,
+}
+
+#[derive(Component)]
+pub struct IntentLabel(Entity);
+
+pub fn toggle_brain_inspector(
+    mut keyboard: ResMut<Input<KeyCode>>,
+    mut inspector: ResMut<BrainInspector>,
+    console: Res<Console>,
+) {
+    if keyboard.just_pressed(KeyCode::F3) {
+        keyboard.clear_just_pressed(KeyCode::F3);
+        inspector.enabled = !inspector.enabled;
+        if inspector.enabled {
+            console.write("AI inspector on", Color::GRAY);
+        } else {
+            console.write("AI inspector off", Color::GRAY);
+        }
+    }
+}
+
+pub fn update_intent_labels(
+    inspector: Res<BrainInspector>,
+    brains: Query<(Entity, &Brain, &Transform, Option<&PackMember>), Without<IntentLabel>>,
+    mut labels: Query<(Entity, &IntentLabel, &mut Transform, &mut Text), Without<Brain>>,
+    assets: Res<GameAssets>,
+    mut commands: Commands,
+) {
+    if !inspector.enabled {
+        labels
+            .iter()
+            .for_each(|(label, _, _, _)| commands.entity(label).despawn());
+        return;
+    }
+
+    let mut labelled = HashSet::default();
+    for (label, owner, mut transform, mut text) in labels.iter_mut() {
+        if let Ok((_, brain, owner_transform, pack)) = brains.get(owner.0) {
+            labelled.insert(owner.0);
+            transform.translation = label_position(owner_transform);
+            text.sections[0].value = describe(brain, pack);
+        } else {
+            commands.entity(label).despawn();
+        }
+    }
+
+    for (owner, brain, owner_transform, pack) in brains.iter() {
+        if labelled.contains(&owner) {
+            continue;
+        }
+        commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::with_section(
+                    describe(brain, pack),
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: 12.0,
+                        color: Color::CYAN,
+                    },
+                    TextAlignment {
+                        horizontal: HorizontalAlign::Center,
+                        vertical: VerticalAlign::Center,
+                    },
+                ),
+                transform: Transform::from_translation(label_position(owner_transform)),
+                ..default()
+            })
+            .insert(IntentLabel(owner))
+            .insert(MapElement)
+            .insert(GameElement);
+    }
+}
+
+fn label_position(owner: &Transform) -> Vec3 {
+    // Above the fog, so you can see what the monsters in the dark are up to
+    Vec3::new(owner.translation.x, owner.translation.y + 22.0, 4.0)
+}
+
+fn describe(brain: &Brain, pack: Option<&PackMember>) -> String {
+    let description = format!("{} {:.2}", brain.intent.name(), brain.score);
+    match pack {
+        // Only worth mentioning while the pack is actually hunting
+        Some(member) if member.station.is_some() => {
+            format!("{} [{}]", description, member.role.name())
+        }
+        _ => description,
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{
+    combat::Dead,
+    factions::{Faction, Relationships},
+    fov::FieldOfView,
+    maps::TilePosition,
+    status::{is_unconscious, StatusEffects},
+    stealth::{Awareness, Stealth},
+    TimeStepResource,
+};
+use bevy::{prelude::*, utils::HashMap};
+use bracket_pathfinding::prelude::Point;
+
+/// Forget about a target you haven't seen for this many ticks
+const MEMORY_TICKS: u64 = 600;
+/// How long to poke around where you lost them before giving up
+const SEARCH_TICKS: u64 = 240;
+
+#[derive(Clone, Copy)]
+pub struct Sighting {
+    pub position: (i32, i32),
+    /// The world tick it was seen (or heard) on
+    pub seen_at: u64,
+}
+
+#[derive(Clone, Copy)]
+pub struct Search {
+    pub center: (i32, i32),
+    pub until: u64,
+}
+
+/// Where an actor last saw each of its targets, and whether it's hunting around for them
+#[derive(Component, Default)]
+pub struct Memory {
+    pub sightings: HashMap<Entity, Sighting>,
+    pub search: Option<Search>,
+}
+
+impl Memory {
+    pub fn freshest(&self) -> Option<(Entity, Sighting)> {
+        self.sightings
+            .iter()
+            .max_by_key(|(_, sighting)| sighting.seen_at)
+            .map(|(entity, sighting)| (*entity, *sighting))
+    }
+}
+
+pub fn remember_targets(
+    mut actors: Query<
+        (
+            Entity,
+            &TilePosition,
+            &FieldOfView,
+            &Faction,
+            &mut Memory,
+            Option<&Awareness>,
+            Option<&StatusEffects>,
+        ),
+        Without<Dead>,
+    >,
+    others: Query<(Entity, &TilePosition, &Faction, Option<&StatusEffects>), Without<Dead>>,
+    stealthy: Query<(), With<Stealth>>,
+    relationships: Res<Relationships>,
+    timer: Res<TimeStepResource>,
+) {
+    if !timer.timer.finished() {
+        return;
+    }
+    let now = timer.ticks;
+    for (entity, pos, fov, faction, mut memory, awareness, effects) in actors.iter_mut() {
+        if is_unconscious(effects) {
+            continue;
+        }
+        let mut seen_any = false;
+        for (other, other_pos, other_faction, other_effects) in others.iter() {
+            // Hidden things only get remembered once they've raised suspicion
+            let spotted = stealthy.get(other).is_err()
+                || awareness.map_or(true, |awareness| awareness.suspicious(other));
+            if other != entity
+                && !is_unconscious(other_effects)
+                && spotted
+                && relationships.is_hostile(*faction, *other_faction)
+                && fov.fov_set.contains(&Point::new(other_pos.x, other_pos.y))
+            {
+                seen_any = true;
+                memory.sightings.insert(
+                    other,
+                    Sighting {
+                        position: (other_pos.x, other_pos.y),
+                        seen_at: now,
+                    },
+                );
+            }
+        }
+
+        // Targets that died, left the region or just slipped your mind
+        memory.sightings.retain(|target, sighting| {
+            others
+                .get(*target)
+                .is_ok_and(|(_, _, _, effects)| !is_unconscious(effects))
+                && now.saturating_sub(sighting.seen_at) < MEMORY_TICKS
+        });
+
+        if seen_any {
+            memory.search = None;
+        } else if let Some((target, sighting)) = memory.freshest() {
+            // Got to where they were, and they're not here. Have a sniff around.
+            if sighting.position == (pos.x, pos.y) {
+                memory.sightings.remove(&target);
+                memory.search = Some(Search {
+                    center: sighting.position,
+                    until: now + SEARCH_TICKS,
+                });
+            }
+        }
+        if matches!(memory.search, Some(search) if search.until < now) {
+            memory.search = None;
+        }
+    }
+}
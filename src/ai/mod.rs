@@ -1,12 +1,34 @@
 mod animation;
 mod attack;
+mod behaviours;
+mod brain;
 mod chase;
 mod facing;
 mod flee;
+mod flock;
+mod inspect;
+mod memory;
 mod movement;
+mod orders;
+mod pack;
+mod search;
+mod travel;
+mod wander;
+mod webs;
 pub use animation::*;
 pub use attack::*;
+pub use behaviours::*;
+pub use brain::*;
 pub use chase::*;
 pub use facing::*;
 pub use flee::*;
+pub use flock::*;
+pub use inspect::*;
+pub use memory::*;
 pub use movement::*;
+pub use orders::*;
+pub use pack::*;
+pub use search::*;
+pub use travel::*;
+pub use wander::*;
+pub use webs::*;
@@ -1,8 +1,16 @@
-use super::{AnimationSet, Facing};
-use crate::{combat::LerpAttack, maps::LerpMove};
-use bevy::{prelude::*, utils::HashMap};
+use crate::{
+    actors::Player,
+    combat::{Dead, LerpAttack},
+    maps::{LerpMove, Occupancy, RegionMap, TilePosition},
+    status::{can_act, is_unconscious, StatusEffects},
+    turns::{Initiative, Turns, ACTION_COST},
+};
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub enum Action {
     Move {
         from: (i32, i32),
@@ -14,6 +22,10 @@ pub enum Action {
         to: (i32, i32),
         target: Entity,
     },
+    Bump {
+        from: (i32, i32),
+        toward: (i32, i32),
+    },
 }
 
 #[derive(Clone, Copy)]
@@ -23,76 +35,188 @@ pub struct ActionRequest {
     pub priority: i32,
 }
 
+/// Why process_actions turned an action down
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RejectReason {
+    /// The actor is dead, knocked out or stunned
+    Incapacitated,
+    /// The actor is still busy with its last move or attack
+    Busy,
+    /// The actor has moved since it asked
+    Stale,
+    /// The map doesn't allow it
+    Blocked,
+    /// Somebody else is standing there, or got there first
+    Occupied,
+    /// The target is gone, dead or out of reach
+    NoTarget,
+    /// Turn-based mode, and the actor hasn't the energy
+    OutOfTurn,
+    /// Stuck in a web, so it can fight but not move
+    Rooted,
+}
+
+/// Sent whenever a requested action doesn't happen, so AI can try something else
+pub struct ActionRejected {
+    pub entity: Entity,
+    pub action: Action,
+    pub reason: RejectReason,
+}
+
+impl ActionRequest {
+    /// A total order over requests for the same entity: priority first, then attacks over
+    /// moves over bumps, then by target tile. Event order never matters.
+    fn rank(&self) -> (i32, u8, (i32, i32)) {
+        match self.action {
+            Action::WantsToAttack { to, .. } => (self.priority, 2, to),
+            Action::Move { to, .. } => (self.priority, 1, to),
+            Action::Bump { toward, .. } => (self.priority, 0, toward),
+        }
+    }
+}
+
 pub fn process_actions(
     mut actions: EventReader<ActionRequest>,
+    mut rejections: EventWriter<ActionRejected>,
     mut commands: Commands,
-    animation_query: Query<(Entity, &AnimationSet)>,
-    exists_query: Query<Entity>,
+    actors: Query<(
+        &TilePosition,
+        Option<&Dead>,
+        Option<&StatusEffects>,
+        Option<&LerpMove>,
+        Option<&LerpAttack>,
+    )>,
+    targets: Query<(&TilePosition, Option<&StatusEffects>), Without<Dead>>,
+    mut initiative: Query<(&mut Initiative, Option<&Player>)>,
+    mut turns: ResMut<Turns>,
+    map: Res<RegionMap>,
+    occupancy: Res<Occupancy>,
 ) {
+    // Each entity gets its single best request; the losers aren't rejections, just outranked
     let mut final_action: HashMap<Entity, ActionRequest> = HashMap::new();
     for action in actions.iter() {
-        if let Some(current) = final_action.get_mut(&action.entity) {
-            if current.priority < action.priority {
-                current.action = action.action;
+        match final_action.get(&action.entity) {
+            Some(current) if current.rank() >= action.rank() => {}
+            _ => {
+                final_action.insert(action.entity, *action);
             }
-        } else {
-            final_action.insert(action.entity, *action);
         }
     }
 
-    // Process the resultant query list
-    for (entity, action) in final_action.iter() {
-        match action.action {
-            Action::Move { from, to, jumping } => {
-                let direction = if from.0 < to.0 {
-                    Facing::Right
-                } else if from.0 > to.0 {
-                    Facing::Left
-                } else if from.1 < to.1 {
-                    Facing::Down
-                } else if from.1 > to.1 {
-                    Facing::Up
-                } else {
-                    Facing::Left
-                };
+    // Highest priority goes first, with ties broken by entity order (not age, since that
+    // compares generations first), so two actors wanting the same tile always settle it the
+    // same way
+    let mut ordered: Vec<ActionRequest> = final_action.values().copied().collect();
+    ordered.sort_by_key(|action| (-action.priority, action.entity));
+    let mut claimed = HashSet::default();
 
-                for e in exists_query.iter() {
-                    if e == *entity {
-                        commands.entity(*entity).insert(LerpMove {
-                            start: from,
-                            end: to,
-                            step: 0,
-                            jumping,
-                            animate: find_animation(&animation_query, *entity, &direction),
+    for request in ordered {
+        let entity = request.entity;
+        let verdict = if let Ok((pos, dead, effects, moving, attacking)) = actors.get(entity) {
+            let here = (pos.x, pos.y);
+            if dead.is_some() || !can_act(effects) {
+                Err(RejectReason::Incapacitated)
+            } else if moving.is_some() || attacking.is_some() {
+                Err(RejectReason::Busy)
+            } else if turns.is_turn_based()
+                && initiative
+                    .get(entity)
+                    .is_ok_and(|(initiative, _)| !initiative.can_act())
+            {
+                Err(RejectReason::OutOfTurn)
+            } else {
+                match request.action {
+                    Action::Move { from, to, jumping } => {
+                        let legal = if jumping {
+                            map.can_jump(from, to)
+                        } else {
+                            map.can_player_enter(to.0, to.1)
+                        };
+                        if from != here {
+                            Err(RejectReason::Stale)
+                        } else if effects.is_some_and(|effects| !effects.can_move()) {
+                            Err(RejectReason::Rooted)
+                        } else if !legal {
+                            Err(RejectReason::Blocked)
+                        } else if occupancy.is_blocked(to.0, to.1, entity) || !claimed.insert(to) {
+                            Err(RejectReason::Occupied)
+                        } else {
+                            Ok(())
+                        }
+                    }
+                    Action::Bump { from, .. } => {
+                        if from != here {
+                            Err(RejectReason::Stale)
+                        } else {
+                            Ok(())
+                        }
+                    }
+                    Action::WantsToAttack { from, target, .. } => {
+                        let in_reach = targets.get(target).is_ok_and(|(target_pos, effects)| {
+                            !is_unconscious(effects)
+                                && (target_pos.x - here.0).abs() <= 1
+                                && (target_pos.y - here.1).abs() <= 1
                         });
+                        if from != here {
+                            Err(RejectReason::Stale)
+                        } else if !in_reach {
+                            Err(RejectReason::NoTarget)
+                        } else {
+                            Ok(())
+                        }
                     }
                 }
             }
-            Action::WantsToAttack { from, to, target } => {
-                for e in exists_query.iter() {
-                    if e == *entity {
-                        commands.entity(action.entity).insert(LerpAttack {
-                            target,
-                            start: from,
-                            end: to,
-                            step: 0,
-                        });
-                    }
+        } else {
+            // Despawned since it asked; there's nobody to tell
+            continue;
+        };
+
+        if let Err(reason) = verdict {
+            rejections.send(ActionRejected {
+                entity,
+                action: request.action,
+                reason,
+            });
+            continue;
+        }
+
+        if turns.is_turn_based() {
+            if let Ok((mut initiative, player)) = initiative.get_mut(entity) {
+                initiative.energy -= ACTION_COST;
+                if player.is_some() {
+                    turns.start_turn();
                 }
             }
         }
-    }
-}
 
-fn find_animation(
-    query: &Query<(Entity, &AnimationSet)>,
-    target: Entity,
-    direction: &Facing,
-) -> Option<Vec<usize>> {
-    for (entity, animations) in query.iter() {
-        if entity == target {
-            return Some(animations.animations[direction.index()].clone());
+        match request.action {
+            Action::Move { from, to, jumping } => {
+                commands.entity(entity).insert(LerpMove {
+                    start: from,
+                    end: to,
+                    step: 0,
+                    jumping,
+                    bump: false,
+                });
+            }
+            Action::Bump { from, toward } => {
+                commands.entity(entity).insert(LerpMove {
+                    start: from,
+                    end: toward,
+                    step: 0,
+                    jumping: true,
+                    bump: true,
+                });
+            }
+            Action::WantsToAttack { from, to, target } => {
+                commands.entity(entity).insert(LerpAttack {
+                    target,
+                    start: from,
+                    end: to,
+                    step: 0,
+                });
+            }
         }
     }
-    None
 }
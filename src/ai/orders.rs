@@ -0,0 +1,164 @@
+use crate::{
+    actors::{Henry, Player},
+    combat::Dead,
+    console::Console,
+    factions::{Faction, Relationships},
+    fov::FieldOfView,
+    maps::{tile_index, MapToBuild, RegionMap, TilePosition, TileType},
+    status::{is_unconscious, StatusEffects},
+};
+use bevy::prelude::*;
+use bracket_pathfinding::prelude::{DistanceAlg, Point};
+
+/// What the player last told their companion to do
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub enum CompanionOrder {
+    Follow,
+    Stay,
+    Attack(Entity),
+    Fetch((i32, i32)),
+    Guard((i32, i32)),
+    Retreat,
+    /// Drive loose chickens back into the coop
+    Herd,
+}
+
+impl CompanionOrder {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CompanionOrder::Follow => "Follow",
+            CompanionOrder::Stay => "Stay",
+            CompanionOrder::Attack(_) => "Attack",
+            CompanionOrder::Fetch(_) => "Fetch",
+            CompanionOrder::Guard(_) => "Guard",
+            CompanionOrder::Retreat => "Retreat",
+            CompanionOrder::Herd => "Herd",
+        }
+    }
+}
+
+/// Anything a companion can carry back to you
+pub fn is_fetchable(tile: TileType) -> bool {
+    matches!(tile, TileType::Key | TileType::Grain)
+}
+
+fn nearest<T: Copy>(from: Point, candidates: impl Iterator<Item = (T, Point)>) -> Option<T> {
+    candidates
+        .map(|(item, pt)| (item, DistanceAlg::Pythagoras.distance2d(from, pt)))
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        .map(|(item, _)| item)
+}
+
+/// Number keys 1-7 give Henry his orders
+pub fn issue_orders(
+    keyboard: Res<Input<KeyCode>>,
+    player: Query<(&TilePosition, &FieldOfView), With<Player>>,
+    mut henry: Query<&mut CompanionOrder, With<Henry>>,
+    others: Query<(Entity, &TilePosition, &Faction, Option<&StatusEffects>), Without<Dead>>,
+    relationships: Res<Relationships>,
+    map: Res<RegionMap>,
+    console: Res<Console>,
+) {
+    let (player_pos, fov) = if let Ok(player) = player.get_single() {
+        player
+    } else {
+        return;
+    };
+    let here = Point::new(player_pos.x, player_pos.y);
+
+    let order = if keyboard.just_pressed(KeyCode::Key1) {
+        console.write("\"Henry, heel!\"", Color::CYAN);
+        Some(CompanionOrder::Follow)
+    } else if keyboard.just_pressed(KeyCode::Key2) {
+        console.write("\"Henry, stay!\"", Color::CYAN);
+        Some(CompanionOrder::Stay)
+    } else if keyboard.just_pressed(KeyCode::Key3) {
+        let target = nearest(
+            here,
+            others
+                .iter()
+                .filter(|(_, pos, faction, effects)| {
+                    !is_unconscious(*effects)
+                        && relationships.is_hostile(Faction::Player, **faction)
+                        && fov.fov_set.contains(&Point::new(pos.x, pos.y))
+                })
+                .map(|(entity, pos, _, _)| (entity, Point::new(pos.x, pos.y))),
+        );
+        if target.is_some() {
+            console.write("\"Henry, get 'em!\"", Color::CYAN);
+        } else {
+            console.write("There's nothing for Henry to attack.", Color::GRAY);
+        }
+        target.map(CompanionOrder::Attack)
+    } else if keyboard.just_pressed(KeyCode::Key4) {
+        let target = nearest(
+            here,
+            fov.fov_set
+                .iter()
+                .filter(|pt| is_fetchable(map.features[tile_index(pt.x, pt.y)]))
+                .map(|pt| ((pt.x, pt.y), *pt)),
+        );
+        if target.is_some() {
+            console.write("\"Henry, fetch!\"", Color::CYAN);
+        } else {
+            console.write("There's nothing for Henry to fetch.", Color::GRAY);
+        }
+        target.map(CompanionOrder::Fetch)
+    } else if keyboard.just_pressed(KeyCode::Key5) {
+        console.write("\"Henry, guard this spot!\"", Color::CYAN);
+        Some(CompanionOrder::Guard((player_pos.x, player_pos.y)))
+    } else if keyboard.just_pressed(KeyCode::Key6) {
+        console.write("\"Henry, run away!\"", Color::CYAN);
+        Some(CompanionOrder::Retreat)
+    } else if keyboard.just_pressed(KeyCode::Key7) {
+        if map.id == MapToBuild::FarmerTomCoup {
+            console.write("\"Henry, round 'em up!\"", Color::CYAN);
+            Some(CompanionOrder::Herd)
+        } else {
+            console.write(
+                "There's no coop around here to herd anything into.",
+                Color::GRAY,
+            );
+            None
+        }
+    } else {
+        None
+    };
+
+    if let Some(new_order) = order {
+        for mut order in henry.iter_mut() {
+            *order = new_order;
+        }
+    }
+}
+
+/// Orders that no longer make sense fall back to following
+pub fn expire_orders(
+    mut henry: Query<&mut CompanionOrder>,
+    targets: Query<Option<&StatusEffects>, Without<Dead>>,
+    map: Res<RegionMap>,
+    mut last_region: Local<Option<MapToBuild>>,
+    console: Res<Console>,
+) {
+    let changed_region = *last_region != Some(map.id);
+    *last_region = Some(map.id);
+
+    for mut order in henry.iter_mut() {
+        let expired = match *order {
+            CompanionOrder::Attack(target) => !targets
+                .get(target)
+                .is_ok_and(|effects| !is_unconscious(effects)),
+            CompanionOrder::Fetch((x, y)) => !is_fetchable(map.features[tile_index(x, y)]),
+            CompanionOrder::Stay | CompanionOrder::Guard(_) | CompanionOrder::Herd => {
+                changed_region
+            }
+            CompanionOrder::Follow | CompanionOrder::Retreat => false,
+        };
+        if expired {
+            if !changed_region {
+                console.write("Henry trots back to your side.", Color::YELLOW);
+            }
+            *order = CompanionOrder::Follow;
+        }
+    }
+}
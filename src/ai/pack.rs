@@ -0,0 +1,309 @@
+use super::{Brain, Intent, Memory, Sighting};
+use crate::{
+    combat::{Dead, DeathMessage, Health},
+    console::Console,
+    maps::{RegionMap, TilePosition},
+    TimeStepResource,
+};
+use bevy::prelude::*;
+
+/// The leader hangs back this far while the rest of the pack closes the ring
+const STALK_DISTANCE: f32 = 3.0;
+/// Flankers and the cut-off wolf take up their posts this far from the prey
+const SURROUND_DISTANCE: f32 = 2.0;
+/// Give up waiting for the ring to close after this long, and just go for it
+const SURROUND_TICKS: u32 = 150;
+/// How long the wolf that calls the pack stands still to howl
+const HOWL_TICKS: u32 = 20;
+/// How long a leaderless pack keeps running before it regroups
+const ROUT_TICKS: u32 = 300;
+/// Standing in for going after the prey, so the inspector shows about the same score
+const STATION_SCORE: f32 = 0.8;
+
+/// What a wolf does for the pack while it hunts
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PackRole {
+    Lead,
+    FlankLeft,
+    FlankRight,
+    CutOff,
+    Howl,
+}
+
+impl PackRole {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PackRole::Lead => "Lead",
+            PackRole::FlankLeft => "Flank left",
+            PackRole::FlankRight => "Flank right",
+            PackRole::CutOff => "Cut off",
+            PackRole::Howl => "Howl",
+        }
+    }
+}
+
+/// Hunts alongside the other wolves on the map, under whichever of them is leading
+#[derive(Component)]
+pub struct PackMember {
+    pub role: PackRole,
+    /// Where the coordinator wants this wolf while the ring closes
+    pub station: Option<(i32, i32)>,
+    howling: u32,
+}
+
+impl Default for PackMember {
+    fn default() -> Self {
+        Self {
+            role: PackRole::Lead,
+            station: None,
+            howling: 0,
+        }
+    }
+}
+
+/// The pack's shared view of the hunt lives with its leader
+#[derive(Component, Default)]
+pub struct PackLeader {
+    target: Option<Entity>,
+    surround_ticks: u32,
+    attacking: bool,
+}
+
+/// Running for it, now the leader is gone
+#[derive(Component)]
+pub struct Routed(pub u32);
+
+fn hunting(intent: Intent) -> Option<Entity> {
+    match intent {
+        Intent::Chase(target) | Intent::Attack(target) => Some(target),
+        _ => None,
+    }
+}
+
+/// The nearest tile to a spot that can actually be stood on
+fn open_tile_near(map: &RegionMap, spot: (i32, i32)) -> Option<(i32, i32)> {
+    [(0, 0), (-1, 0), (1, 0), (0, -1), (0, 1)]
+        .iter()
+        .map(|(dx, dy)| (spot.0 + dx, spot.1 + dy))
+        .find(|tile| map.can_player_enter(tile.0, tile.1))
+}
+
+/// Where each role waits: the leader behind, flankers either side, the cut-off beyond the prey
+fn station(role: PackRole, lap: usize, prey: (i32, i32), leader: (i32, i32)) -> (i32, i32) {
+    let ahead = Vec2::new((prey.0 - leader.0) as f32, (prey.1 - leader.1) as f32)
+        .try_normalize()
+        .unwrap_or(Vec2::Y);
+    let side = Vec2::new(-ahead.y, ahead.x);
+    // Anyone beyond the first ring forms a looser one
+    let reach = SURROUND_DISTANCE + lap as f32;
+    let offset = match role {
+        PackRole::Lead => -ahead * STALK_DISTANCE,
+        PackRole::FlankLeft => side * reach,
+        PackRole::FlankRight => -side * reach,
+        PackRole::CutOff => ahead * reach,
+        PackRole::Howl => Vec2::ZERO,
+    };
+    (
+        prey.0 + offset.x.round() as i32,
+        prey.1 + offset.y.round() as i32,
+    )
+}
+
+/// Runs after everyone has thought, and overrules the pack's chasers until the prey is surrounded
+pub fn coordinate_packs(
+    mut wolves: Query<
+        (
+            Entity,
+            &TilePosition,
+            &Health,
+            &mut Brain,
+            &mut PackMember,
+            Option<&mut PackLeader>,
+            Option<&mut Routed>,
+            Option<&mut Memory>,
+        ),
+        Without<Dead>,
+    >,
+    fallen_leaders: Query<(), (With<PackLeader>, With<Dead>)>,
+    positions: Query<&TilePosition>,
+    mut deaths: EventReader<DeathMessage>,
+    map: Res<RegionMap>,
+    console: Res<Console>,
+    mut commands: Commands,
+    timer: Res<TimeStepResource>,
+) {
+    // Losing the leader breaks the pack
+    for death in deaths.iter() {
+        if fallen_leaders.get(death.entity).is_ok() {
+            for (entity, _, _, _, _, _, _, _) in wolves.iter() {
+                commands.entity(entity).insert(Routed(ROUT_TICKS));
+            }
+            console.write(
+                "With their leader dead, the wolves turn tail!",
+                Color::ORANGE,
+            );
+        }
+    }
+    if !timer.timer.finished() {
+        return;
+    }
+
+    for (entity, _, _, mut brain, _, _, routed, _) in wolves.iter_mut() {
+        if let Some(mut routed) = routed {
+            routed.0 = routed.0.saturating_sub(1);
+            if routed.0 == 0 {
+                commands.entity(entity).remove::<Routed>();
+            }
+            brain.intent = Intent::Flee;
+            brain.score = 1.0;
+            brain.scores.push((Intent::Flee, 1.0));
+        }
+    }
+
+    let has_leader = !fallen_leaders.is_empty()
+        || wolves
+            .iter()
+            .any(|(_, _, _, _, _, leader, _, _)| leader.is_some());
+    if !has_leader {
+        // The toughest wolf takes charge once the pack has regrouped
+        let candidate = wolves
+            .iter()
+            .filter(|(_, _, _, _, _, _, routed, _)| routed.is_none())
+            .max_by_key(|(entity, _, health, _, _, _, _, _)| (health.max, *entity))
+            .map(|(entity, _, _, _, _, _, _, _)| entity);
+        if let Some(leader) = candidate {
+            commands.entity(leader).insert(PackLeader::default());
+        }
+        return;
+    }
+
+    let leader = wolves
+        .iter()
+        .find(|(_, _, _, _, _, leader, routed, _)| leader.is_some() && routed.is_none())
+        .map(|(entity, pos, _, brain, _, _, _, _)| (entity, (pos.x, pos.y), hunting(brain.intent)));
+    let (leader, leader_pos, leader_prey) = if let Some(leader) = leader {
+        leader
+    } else {
+        return;
+    };
+
+    // The pack goes after whatever the leader is after, or failing that whatever anyone spotted
+    let mut members: Vec<(Entity, Option<Entity>)> = wolves
+        .iter()
+        .filter(|(entity, _, _, _, _, _, routed, _)| *entity != leader && routed.is_none())
+        .map(|(entity, _, _, brain, _, _, _, _)| (entity, hunting(brain.intent)))
+        .collect();
+    members.sort_by_key(|(entity, _)| *entity);
+    let prey = leader_prey.or_else(|| members.iter().find_map(|(_, prey)| *prey));
+    let prey_pos = prey
+        .and_then(|prey| positions.get(prey).ok())
+        .map(|pos| (pos.x, pos.y));
+
+    let (prey, prey_pos) = if let (Some(prey), Some(prey_pos)) = (prey, prey_pos) {
+        (prey, prey_pos)
+    } else {
+        if let Ok((_, _, _, _, _, Some(mut pack), _, _)) = wolves.get_mut(leader) {
+            *pack = PackLeader::default();
+        }
+        for (_, _, _, _, mut member, _, _, _) in wolves.iter_mut() {
+            member.station = None;
+        }
+        return;
+    };
+
+    // A new hunt: whoever spotted the prey calls the rest of the pack over
+    let mut fresh_hunt = false;
+    if let Ok((_, _, _, _, _, Some(mut pack), _, _)) = wolves.get_mut(leader) {
+        if pack.target != Some(prey) {
+            *pack = PackLeader {
+                target: Some(prey),
+                ..default()
+            };
+            fresh_hunt = true;
+        }
+    }
+    let unaware: Vec<Entity> = std::iter::once((leader, leader_prey))
+        .chain(members.iter().copied())
+        .filter(|(_, hunting)| *hunting != Some(prey))
+        .map(|(entity, _)| entity)
+        .collect();
+    if fresh_hunt && !unaware.is_empty() {
+        let howler = std::iter::once((leader, leader_prey))
+            .chain(members.iter().copied())
+            .find(|(_, hunting)| *hunting == Some(prey))
+            .map(|(entity, _)| entity);
+        if let Some(howler) = howler {
+            if let Ok((_, _, _, _, mut member, _, _, _)) = wolves.get_mut(howler) {
+                member.howling = HOWL_TICKS;
+            }
+        }
+        let now = timer.ticks;
+        for wolf in unaware {
+            if let Ok((_, _, _, _, _, _, _, Some(mut memory))) = wolves.get_mut(wolf) {
+                memory.sightings.insert(
+                    prey,
+                    Sighting {
+                        position: prey_pos,
+                        seen_at: now,
+                    },
+                );
+                memory.search = None;
+            }
+        }
+        console.write("A wolf howls, and the pack answers!", Color::ORANGE);
+    }
+
+    let roles = [PackRole::FlankLeft, PackRole::FlankRight, PackRole::CutOff];
+    let mut assignments = vec![(leader, PackRole::Lead, 0)];
+    for (i, (entity, _)) in members.iter().enumerate() {
+        assignments.push((*entity, roles[i % roles.len()], i / roles.len()));
+    }
+
+    // Everyone hunting has to be at their post, or already at the prey's throat
+    let mut surrounded = true;
+    for (entity, role, lap) in assignments.iter().copied() {
+        if let Ok((_, pos, _, brain, mut member, _, _, _)) = wolves.get_mut(entity) {
+            member.role = role;
+            member.station = open_tile_near(&map, station(role, lap, prey_pos, leader_pos));
+            if member.howling > 0 {
+                member.role = PackRole::Howl;
+            }
+            let adjacent = (pos.x - prey_pos.0).abs() + (pos.y - prey_pos.1).abs() <= 1;
+            let posted = member
+                .station
+                .map_or(true, |s| (pos.x - s.0).abs() + (pos.y - s.1).abs() <= 1);
+            if hunting(brain.intent) == Some(prey) && !adjacent && !posted {
+                surrounded = false;
+            }
+        }
+    }
+
+    let attacking = if let Ok((_, _, _, _, _, Some(mut pack), _, _)) = wolves.get_mut(leader) {
+        pack.surround_ticks += 1;
+        // A lone wolf has nobody to wait for
+        if surrounded || pack.surround_ticks > SURROUND_TICKS || assignments.len() == 1 {
+            pack.attacking = true;
+        }
+        pack.attacking
+    } else {
+        true
+    };
+
+    for (entity, _, _) in assignments {
+        if let Ok((_, _, _, mut brain, mut member, _, _, _)) = wolves.get_mut(entity) {
+            if member.howling > 0 {
+                member.howling -= 1;
+                brain.intent = Intent::Idle;
+                continue;
+            }
+            if attacking || brain.intent != Intent::Chase(prey) {
+                continue;
+            }
+            if let Some(spot) = member.station {
+                brain.intent = Intent::GoTo(spot);
+                brain.score = STATION_SCORE;
+                brain.scores.push((Intent::GoTo(spot), STATION_SCORE));
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use super::{Action, ActionRequest, Brain, Intent};
+use crate::{
+    combat::{Dead, LerpAttack},
+    maps::{LerpMove, RegionMap, TilePosition},
+    random::Rng,
+    TimeStepResource,
+};
+use bevy::prelude::*;
+
+/// Searchers don't stray further than this from where they lost the trail
+const SEARCH_RADIUS: i32 = 3;
+
+/// Snuffles around the spot a target was last seen
+pub fn search(
+    ai_query: Query<
+        (Entity, &TilePosition, &Brain),
+        (Without<LerpMove>, Without<LerpAttack>, Without<Dead>),
+    >,
+    map: Res<RegionMap>,
+    rng: Res<Rng>,
+    mut actions: EventWriter<ActionRequest>,
+    timer: Res<TimeStepResource>,
+) {
+    if !timer.timer.finished() {
+        return;
+    }
+    for (entity, pos, brain) in ai_query.iter() {
+        if let Intent::Search(center) = brain.intent {
+            // Stop and sniff most of the time
+            if rng.range(0, 6) != 0 {
+                continue;
+            }
+            let (dx, dy) = match rng.range(0, 4) {
+                0 => (-1, 0),
+                1 => (1, 0),
+                2 => (0, -1),
+                _ => (0, 1),
+            };
+            let to = (pos.x + dx, pos.y + dy);
+            let in_range = (to.0 - center.0).abs() <= SEARCH_RADIUS
+                && (to.1 - center.1).abs() <= SEARCH_RADIUS;
+            if in_range && map.can_player_enter(to.0, to.1) {
+                actions.send(ActionRequest {
+                    entity,
+                    action: Action::Move {
+                        from: (pos.x, pos.y),
+                        to,
+                        jumping: false,
+                    },
+                    priority: 1,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use super::{Action, ActionRequest, Brain, Intent};
+use crate::{
+    combat::{Dead, LerpAttack},
+    maps::{LerpMove, Navigator, Occupancy, RegionMap, TilePosition},
+    TimeStepResource,
+};
+use bevy::prelude::*;
+
+/// Heads for a specific tile, jumping where it helps
+pub fn travel(
+    ai_query: Query<
+        (Entity, &TilePosition, &Brain),
+        (Without<LerpMove>, Without<LerpAttack>, Without<Dead>),
+    >,
+    map: Res<RegionMap>,
+    occupancy: Res<Occupancy>,
+    mut actions: EventWriter<ActionRequest>,
+    timer: Res<TimeStepResource>,
+) {
+    if !timer.timer.finished() {
+        return;
+    }
+    for (entity, pos, brain) in ai_query.iter() {
+        if let Intent::GoTo(destination) | Intent::Investigate(destination) = brain.intent {
+            let here = (pos.x, pos.y);
+            if here == destination {
+                continue;
+            }
+            if let Some(step) = Navigator::new(&map)
+                .with_jumps()
+                .avoiding(&occupancy, entity)
+                .path(here, destination)
+                .and_then(|path| path.first().copied())
+            {
+                actions.send(ActionRequest {
+                    entity,
+                    action: Action::Move {
+                        from: here,
+                        to: step,
+                        jumping: (step.0 - here.0).abs() + (step.1 - here.1).abs() > 1,
+                    },
+                    priority: 2,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use super::{Action, ActionRequest, Brain, Intent};
+use crate::{
+    combat::{Dead, LerpAttack},
+    maps::{LerpMove, RegionMap, TilePosition},
+    random::Rng,
+    TimeStepResource,
+};
+use bevy::prelude::*;
+
+pub fn wander(
+    map: Res<RegionMap>,
+    ai_query: Query<
+        (Entity, &TilePosition, &Brain),
+        (Without<LerpMove>, Without<LerpAttack>, Without<Dead>),
+    >,
+    rng: Res<Rng>,
+    mut actions: EventWriter<ActionRequest>,
+    timer: Res<TimeStepResource>,
+) {
+    if !timer.timer.finished() {
+        return;
+    }
+    for (entity, pos, brain) in ai_query.iter() {
+        if brain.intent != Intent::Wander {
+            continue;
+        }
+        // Do wandering things, each wanderer its own way
+        let delta = match rng.range(0, 100) {
+            6 => Some((-1, 0)),
+            7 => Some((1, 0)),
+            8 => Some((0, -1)),
+            9 => Some((0, 1)),
+            _ => None,
+        };
+
+        if let Some(delta) = delta {
+            if map.can_player_enter(pos.x + delta.0, pos.y + delta.1) {
+                actions.send(ActionRequest {
+                    entity,
+                    action: Action::Move {
+                        from: (pos.x, pos.y),
+                        to: (pos.x + delta.0, pos.y + delta.1),
+                        jumping: false,
+                    },
+                    priority: 1,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use super::{Brain, Intent};
+use crate::{
+    actors::{Player, Spider},
+    combat::{Dead, LerpAttack},
+    console::Console,
+    fov::FieldOfView,
+    maps::{tile_index, tile_to_screen, LerpMove, MapElement, RegionMap, TilePosition, TileType},
+    random::Rng,
+    status::{Effect, StatusEffects},
+    GameElement, TimeStepResource,
+};
+use bevy::prelude::*;
+use bracket_pathfinding::prelude::{Algorithm2D, Point};
+
+/// Spiders only weave this close to home
+const LAIR_RADIUS: i32 = 3;
+/// ...and stop once home is this well furnished
+const MAX_WEBS: usize = 6;
+/// A one in this many chance per tick of weaving, while there's nothing to hunt
+const WEAVE_CHANCE: i32 = 300;
+const WEB_RANGE: i32 = 5;
+const WEB_COOLDOWN: u32 = 180;
+/// Ticks a shot spends crossing each tile
+const WEB_SPEED: u32 = 2;
+const ROOT_TICKS: u32 = 60;
+/// Blundering into a web holds you for less time than being shot with one
+const SNARE_TICKS: u32 = 40;
+/// Waiting spiders pounce once their prey comes this close, or can't get away
+const POUNCE_RANGE: i32 = 2;
+const AMBUSH_SCORE: f32 = 0.6;
+
+/// Spins new webs around the spot it calls home
+#[derive(Component, Default)]
+pub struct Weaves {
+    lair: Option<(i32, i32)>,
+}
+
+/// Spits sticky webbing at prey that keeps its distance
+#[derive(Component, Default)]
+pub struct ShootsWebs {
+    cooldown: u32,
+}
+
+/// Waits on its webs for prey to come to it
+#[derive(Component)]
+pub struct Ambushes;
+
+/// A glob of web on its way to where its target was standing
+#[derive(Component)]
+pub struct WebShot {
+    target: Entity,
+    start: (i32, i32),
+    end: (i32, i32),
+    step: u32,
+}
+
+/// What the spider would be chasing, had it not decided to sit tight
+fn prey(brain: &Brain) -> Option<Entity> {
+    if let Intent::Chase(target) = brain.intent {
+        return Some(target);
+    }
+    brain
+        .scores
+        .iter()
+        .filter_map(|(intent, score)| match intent {
+            Intent::Chase(target) => Some((*target, *score)),
+            _ => None,
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(target, _)| target)
+}
+
+fn tile_distance(a: (i32, i32), b: (i32, i32)) -> i32 {
+    i32::max((a.0 - b.0).abs(), (a.1 - b.1).abs())
+}
+
+fn webs_near(map: &RegionMap, center: (i32, i32), radius: i32) -> Vec<(i32, i32)> {
+    (-radius..=radius)
+        .flat_map(|dy| (-radius..=radius).map(move |dx| (center.0 + dx, center.1 + dy)))
+        .filter(|(x, y)| {
+            map.in_bounds(Point::new(*x, *y)) && map.features[tile_index(*x, *y)] == TileType::Web
+        })
+        .collect()
+}
+
+/// While there's nothing to hunt, spiders spin webs on the open ground around their lair
+pub fn weave_webs(
+    mut spiders: Query<
+        (&TilePosition, &Brain, &mut Weaves),
+        (Without<LerpMove>, Without<LerpAttack>, Without<Dead>),
+    >,
+    mut map: ResMut<RegionMap>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    rng: Res<Rng>,
+    timer: Res<TimeStepResource>,
+) {
+    if !timer.timer.finished() {
+        return;
+    }
+    for (pos, brain, mut weaves) in spiders.iter_mut() {
+        let here = (pos.x, pos.y);
+        let lair = *weaves.lair.get_or_insert(here);
+        if matches!(brain.intent, Intent::Chase(_) | Intent::Attack(_))
+            || tile_distance(here, lair) > LAIR_RADIUS
+            || rng.range(0, WEAVE_CHANCE) != 0
+        {
+            continue;
+        }
+        if webs_near(&map, lair, LAIR_RADIUS).len() >= MAX_WEBS {
+            continue;
+        }
+        let open: Vec<(i32, i32)> = [(0, 0), (-1, 0), (1, 0), (0, -1), (0, 1)]
+            .iter()
+            .map(|(dx, dy)| (here.0 + dx, here.1 + dy))
+            .filter(|(x, y)| {
+                tile_distance((*x, *y), lair) <= LAIR_RADIUS
+                    && map.can_player_enter(*x, *y)
+                    && map.features[tile_index(*x, *y)] == TileType::None
+            })
+            .collect();
+        if let Some((x, y)) = rng.random_slice_entry(&open) {
+            map.features[tile_index(*x, *y)] = TileType::Web;
+            map.rebuild_features(&mut meshes);
+        }
+    }
+}
+
+/// Spiders would rather you came to them. They wait on a web until you're close, or stuck.
+pub fn ambush(
+    mut spiders: Query<
+        (&TilePosition, &mut Brain, Option<&Weaves>),
+        (With<Ambushes>, Without<Dead>),
+    >,
+    prey: Query<(&TilePosition, Option<&StatusEffects>)>,
+    map: Res<RegionMap>,
+    timer: Res<TimeStepResource>,
+) {
+    if !timer.timer.finished() {
+        return;
+    }
+    for (pos, mut brain, weaves) in spiders.iter_mut() {
+        let here = (pos.x, pos.y);
+        let pounce = match brain.intent {
+            Intent::Chase(target) => prey.get(target).is_ok_and(|(target_pos, effects)| {
+                effects.is_some_and(|effects| !effects.can_move())
+                    || tile_distance(here, (target_pos.x, target_pos.y)) <= POUNCE_RANGE
+            }),
+            Intent::Idle | Intent::Wander => false,
+            _ => continue,
+        };
+        if pounce || map.features[tile_index(here.0, here.1)] == TileType::Web {
+            if !pounce && brain.intent != Intent::Idle {
+                brain.intent = Intent::Idle;
+                brain.score = AMBUSH_SCORE;
+                brain.scores.push((Intent::Idle, AMBUSH_SCORE));
+            }
+            continue;
+        }
+        // Back to the nearest web at home, or any web close by
+        let home = weaves.and_then(|weaves| weaves.lair).unwrap_or(here);
+        let web = webs_near(&map, home, LAIR_RADIUS)
+            .into_iter()
+            .min_by_key(|web| tile_distance(here, *web));
+        if let Some(web) = web {
+            brain.intent = Intent::GoTo(web);
+            brain.score = AMBUSH_SCORE;
+            brain.scores.push((Intent::GoTo(web), AMBUSH_SCORE));
+        }
+    }
+}
+
+/// Prey that won't come close gets a faceful of web
+pub fn shoot_webs(
+    mut spiders: Query<
+        (&TilePosition, &Brain, &FieldOfView, &mut ShootsWebs),
+        (Without<LerpMove>, Without<LerpAttack>, Without<Dead>),
+    >,
+    targets: Query<&TilePosition>,
+    mut commands: Commands,
+    timer: Res<TimeStepResource>,
+) {
+    if !timer.timer.finished() {
+        return;
+    }
+    for (pos, brain, fov, mut shooter) in spiders.iter_mut() {
+        shooter.cooldown = shooter.cooldown.saturating_sub(1);
+        let target = match prey(brain) {
+            Some(target) if shooter.cooldown == 0 => target,
+            _ => continue,
+        };
+        let target_pos = if let Ok(target_pos) = targets.get(target) {
+            (target_pos.x, target_pos.y)
+        } else {
+            continue;
+        };
+        let here = (pos.x, pos.y);
+        let range = tile_distance(here, target_pos);
+        if range <= 1
+            || range > WEB_RANGE
+            || !fov
+                .fov_set
+                .contains(&Point::new(target_pos.0, target_pos.1))
+        {
+            continue;
+        }
+        shooter.cooldown = WEB_COOLDOWN;
+        let screen = tile_to_screen(here.0, here.1);
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgba(0.9, 0.9, 0.9, 0.9),
+                    custom_size: Some(Vec2::new(8.0, 8.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(screen.0, screen.1, 3.0),
+                ..default()
+            })
+            .insert(WebShot {
+                target,
+                start: here,
+                end: target_pos,
+                step: 0,
+            })
+            .insert(MapElement)
+            .insert(GameElement);
+    }
+}
+
+/// Shots fly to where their target was standing, and stick to it if it hasn't moved
+pub fn fly_web_shots(
+    mut shots: Query<(Entity, &mut WebShot, &mut Transform)>,
+    mut victims: Query<
+        (&TilePosition, &mut StatusEffects, Option<&Player>),
+        (Without<Spider>, Without<Dead>),
+    >,
+    mut commands: Commands,
+    console: Res<Console>,
+    timer: Res<TimeStepResource>,
+) {
+    if !timer.timer.finished() {
+        return;
+    }
+    for (entity, mut shot, mut transform) in shots.iter_mut() {
+        shot.step += 1;
+        let length = tile_distance(shot.start, shot.end) as u32 * WEB_SPEED;
+        let start = tile_to_screen(shot.start.0, shot.start.1);
+        let end = tile_to_screen(shot.end.0, shot.end.1);
+        let progress = f32::min(1.0, shot.step as f32 / length.max(1) as f32);
+        transform.translation.x = start.0 + (end.0 - start.0) * progress;
+        transform.translation.y = start.1 + (end.1 - start.1) * progress;
+        if shot.step < length {
+            continue;
+        }
+
+        commands.entity(entity).despawn();
+        // Anything else standing there is none of the spider's business
+        if let Ok((victim_pos, mut effects, player)) = victims.get_mut(shot.target) {
+            if (victim_pos.x, victim_pos.y) == shot.end {
+                effects.apply(Effect::Rooted, ROOT_TICKS);
+                if player.is_some() {
+                    console.write("A glob of web pins you to the spot!", Color::ORANGE);
+                }
+            }
+        }
+    }
+}
+
+/// Webs are sticky for everyone but spiders. Struggling free tears them down.
+pub fn sticky_webs(
+    mut walkers: Query<
+        (&TilePosition, &mut StatusEffects, Option<&Player>),
+        (Changed<TilePosition>, Without<Spider>, Without<Dead>),
+    >,
+    mut map: ResMut<RegionMap>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    console: Res<Console>,
+) {
+    let mut torn = false;
+    for (pos, mut effects, player) in walkers.iter_mut() {
+        let idx = tile_index(pos.x, pos.y);
+        if map.features[idx] != TileType::Web {
+            continue;
+        }
+        map.features[idx] = TileType::None;
+        torn = true;
+        effects.apply(Effect::Rooted, SNARE_TICKS);
+        if player.is_some() {
+            console.write("You blunder into a web, and get stuck fast!", Color::ORANGE);
+        }
+    }
+    if torn {
+        map.rebuild_features(&mut meshes);
+    }
+}
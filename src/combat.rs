@@ -1,8 +1,15 @@
 use crate::{
-    actors::{Henry, Player},
+    actors::{Henry, Injuries, Player, WhiteWolf, KNOCKOUT_TICKS},
     //ai::ActionRequest,
+    ai::{CompanionOrder, Recruited},
     assets::GameAssets,
+    herding::Herding,
+    interactions::Interaction,
+    items::Inventory,
     maps::{tile_to_screen, TilePosition},
+    status::{Effect, StatusEffects},
+    stealth::Stealth,
+    story::StoryFlags,
     GameElement,
     GameState,
     TimeStepResource,
@@ -18,20 +25,33 @@ pub struct Health {
 #[derive(Component)]
 pub struct PlayerHealthLabel;
 
-#[derive(Component)]
-pub struct Hostile;
-
 pub struct DamageMessage {
     pub from: Option<Entity>,
     pub to: Entity,
 }
 
-#[derive(Component)]
-pub struct Unconscious(pub u32);
+/// Sent when something dies for good, while its entity is still around to look at
+pub struct DeathMessage {
+    pub entity: Entity,
+    pub position: (i32, i32),
+}
 
 #[derive(Component)]
 pub struct Dead;
 
+/// How long a body lies around, fading out over the last FADE_TICKS
+const CORPSE_TICKS: u32 = 150;
+const FADE_TICKS: u32 = 60;
+
+#[derive(Component)]
+pub struct Corpse(u32);
+
+impl Default for Corpse {
+    fn default() -> Self {
+        Self(CORPSE_TICKS)
+    }
+}
+
 pub fn setup_health_hud(commands: &mut Commands, assets: &GameAssets) {
     commands
         .spawn_bundle(TextBundle {
@@ -67,19 +87,58 @@ pub fn setup_health_hud(commands: &mut Commands, assets: &GameAssets) {
 
 pub fn update_health_hud(
     mut health_hud: Query<&mut Text, With<PlayerHealthLabel>>,
-    player_health: Query<&Health, With<Player>>,
-    henry_health: Query<&Health, With<Henry>>,
+    player_health: Query<(&Health, &Inventory, &Stealth, &StatusEffects), With<Player>>,
+    henry_health: Query<(&Health, &CompanionOrder, &Injuries, &StatusEffects), With<Henry>>,
+    flock: Query<(), (With<Recruited>, Without<Dead>)>,
+    herding: Res<Herding>,
 ) {
     let henry = henry_health.get_single();
     let player = player_health.get_single();
 
     for mut txt in health_hud.iter_mut() {
         let mut new_text = "HEALTH:\n".to_string();
-        if let Ok(player) = player {
+        if let Ok((player, _, stealth, effects)) = player {
             new_text += &format!("You: {}/{}", player.current, player.max);
+            let names: Vec<&str> = effects.iter().map(|status| status.effect.name()).collect();
+            if !names.is_empty() {
+                new_text += &format!(" ({})", names.join(", "));
+            } else if stealth.sneaking {
+                new_text += " (sneaking)";
+            } else if stealth.concealment < 0.5 {
+                new_text += " (hidden)";
+            }
+        }
+        if let Ok((henry, order, injuries, effects)) = henry {
+            if let Some(out_cold) = effects.get(Effect::Unconscious) {
+                // The time step runs 30 times a second
+                new_text += &format!("\nHenry: out cold ({}s)", out_cold.ticks.div_ceil(30));
+            } else {
+                new_text += &format!("\nHenry: {}/{}", henry.current, henry.max);
+            }
+            if injuries.stacks > 0 {
+                new_text += &format!("\nHenry's injuries: {}", injuries.stacks);
+            }
+            new_text += &format!("\nOrders: {}", order.name());
         }
-        if let Ok(henry) = henry {
-            new_text += &format!("\nHenry: {}/{}", henry.current, henry.max);
+        if let Ok((_, inventory, _, _)) = player {
+            if inventory.keys > 0 {
+                new_text += &format!("\nKeys: {}", inventory.keys);
+            }
+            if inventory.grain > 0 {
+                new_text += &format!("\nGrain: {}", inventory.grain);
+            }
+        }
+        let flock_size = flock.iter().count();
+        if flock_size > 0 {
+            new_text += &format!("\nFlock: {} chickens", flock_size);
+        }
+        if herding.active {
+            new_text += &format!(
+                "\nHerding: {}/{} penned ({}s)",
+                herding.penned,
+                herding.goal,
+                herding.ticks_left.div_ceil(30)
+            );
         }
         txt.sections[0].value = new_text;
     }
@@ -125,36 +184,56 @@ pub fn combat_lerp(
 
 pub fn damage_system(
     mut events: EventReader<DamageMessage>,
+    mut deaths: EventWriter<DeathMessage>,
     mut commands: Commands,
     mut queries: ParamSet<(
-        Query<(Entity, &mut Health, Option<&Henry>, Option<&Player>)>,
+        Query<(
+            Entity,
+            &mut Health,
+            &TilePosition,
+            Option<&Henry>,
+            Option<&Player>,
+            Option<&WhiteWolf>,
+            Option<&mut StatusEffects>,
+        )>,
         Query<(Entity, &mut Transform, &mut Health)>,
     )>,
     mut state: ResMut<State<GameState>>,
+    mut story: ResMut<StoryFlags>,
     //mut action_queue: ResMut<Events<ActionRequest>>,
 ) {
     let mut killers = Vec::new();
     for damage in events.iter() {
-        for (e, mut health, henry, player) in queries.p0().iter_mut() {
+        for (e, mut health, pos, henry, player, white_wolf, effects) in queries.p0().iter_mut() {
             if e == damage.to {
                 health.current -= 1;
-                if health.current < 1 {
+                // Only the blow that takes it down counts, not any that land the same frame
+                if health.current == 0 {
                     if let Some(from) = damage.from {
                         killers.push(from);
                     }
-                    if henry.is_some() {
+                    if let (Some(_), Some(mut effects)) = (henry, effects) {
                         // Knock poor Henry out
-                        commands.entity(e).insert(Unconscious(30));
+                        effects.apply(Effect::Unconscious, KNOCKOUT_TICKS);
                         health.current = health.max;
                     } else if player.is_some() {
                         // End the game
                         let _ = state.set(GameState::Dead);
                     } else {
-                        //action_queue.update();
-                        //commands.entity(e).despawn();
-                        commands.entity(e).insert(Dead);
-                        commands.entity(e).remove::<Sprite>();
-                        commands.entity(e).remove::<TextureAtlasSprite>();
+                        if white_wolf.is_some() {
+                            story.white_wolf_defeated = true;
+                        }
+                        // No more health means spikes and swipes leave the body alone
+                        commands
+                            .entity(e)
+                            .insert(Dead)
+                            .insert(Corpse::default())
+                            .remove::<Health>()
+                            .remove::<Interaction>();
+                        deaths.send(DeathMessage {
+                            entity: e,
+                            position: (pos.x, pos.y),
+                        });
                     }
                 }
             }
@@ -170,3 +249,33 @@ pub fn damage_system(
         }
     }
 }
+
+/// Bodies fade away, then are gone for good
+pub fn decay_corpses(
+    mut corpses: Query<(
+        Entity,
+        &mut Corpse,
+        Option<&mut Sprite>,
+        Option<&mut TextureAtlasSprite>,
+    )>,
+    mut commands: Commands,
+    timer: Res<TimeStepResource>,
+) {
+    if !timer.timer.finished() {
+        return;
+    }
+    for (entity, mut corpse, sprite, atlas_sprite) in corpses.iter_mut() {
+        corpse.0 = corpse.0.saturating_sub(1);
+        if corpse.0 == 0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let alpha = f32::min(1.0, corpse.0 as f32 / FADE_TICKS as f32);
+        if let Some(mut sprite) = sprite {
+            sprite.color.set_a(alpha);
+        }
+        if let Some(mut sprite) = atlas_sprite {
+            sprite.color.set_a(alpha);
+        }
+    }
+}
@@ -14,7 +14,7 @@ impl Console {
         let mut text = vec![(String::new(), Color::WHITE); NUM_LINES];
         text[0] = ("Welcome to Mega-Chicken".to_string(), Color::YELLOW);
         text[1] = (
-            "Use cursor keys to move, J to jump, SPACE to interact with the object you are facing."
+            "Use cursor keys to move (hold SHIFT to sneak), J to jump, SPACE to interact with the object you are facing, M for the map, 1-7 to give Henry orders."
                 .to_string(),
             Color::CYAN,
         );
@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+#[derive(Component, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Faction {
+    Player,
+    Companion,
+    Critters,
+    /// Chickens that have joined the Mega Chicken
+    Flock,
+    Farmers,
+    Wolves,
+    Spiders,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Relation {
+    Hostile,
+    Afraid,
+    Friendly,
+    Neutral,
+}
+
+/// Who hunts whom, and who runs away from whom. Loaded from factions.ron.
+pub struct Relationships {
+    table: Vec<(Faction, Faction, Relation)>,
+}
+
+impl Relationships {
+    pub fn load() -> Self {
+        Self {
+            table: ron::from_str(include_str!("../assets/factions.ron"))
+                .expect("Unable to parse faction relationships"),
+        }
+    }
+
+    pub fn get(&self, from: Faction, toward: Faction) -> Relation {
+        if from == toward {
+            return Relation::Friendly;
+        }
+        self.table
+            .iter()
+            .find(|(a, b, _)| *a == from && *b == toward)
+            .map(|(_, _, relation)| *relation)
+            .unwrap_or(Relation::Neutral)
+    }
+
+    pub fn is_hostile(&self, from: Faction, toward: Faction) -> bool {
+        self.get(from, toward) == Relation::Hostile
+    }
+
+    pub fn is_afraid(&self, from: Faction, toward: Faction) -> bool {
+        self.get(from, toward) == Relation::Afraid
+    }
+
+    /// For when the story changes how someone feels
+    pub fn set(&mut self, from: Faction, toward: Faction, relation: Relation) {
+        self.table.retain(|(a, b, _)| !(*a == from && *b == toward));
+        self.table.push((from, toward, relation));
+    }
+}
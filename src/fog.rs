@@ -0,0 +1,94 @@
+use crate::{
+    actors::Player,
+    fov::FieldOfView,
+    lighting::LightMap,
+    maps::{
+        tile_index, tile_to_screen, MapElement, RegionMap, TilePosition, NUM_TILES_X, NUM_TILES_Y,
+    },
+    GameElement,
+};
+use bevy::prelude::*;
+use bracket_pathfinding::prelude::{Algorithm2D, Point};
+
+const REMEMBERED_ALPHA: f32 = 0.6;
+
+#[derive(Component)]
+pub struct FogTile(usize);
+
+/// Covers every tile with a black square; update_fog punches holes in it.
+pub fn spawn_fog(commands: &mut Commands) {
+    for y in 0..NUM_TILES_Y as i32 {
+        for x in 0..NUM_TILES_X as i32 {
+            let pos = tile_to_screen(x, y);
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::BLACK,
+                        custom_size: Some(Vec2::new(32.0, 32.0)),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(pos.0, pos.1, 3.0),
+                    ..default()
+                })
+                .insert(FogTile(tile_index(x, y)))
+                .insert(MapElement)
+                .insert(GameElement);
+        }
+    }
+}
+
+pub fn reveal_map(
+    player: Query<&FieldOfView, (With<Player>, Changed<FieldOfView>)>,
+    mut map: ResMut<RegionMap>,
+) {
+    for fov in player.iter() {
+        for pt in fov.fov_set.iter() {
+            // Only touch the map for new tiles, so it isn't flagged as changed every move
+            if map.in_bounds(*pt) && !map.revealed[tile_index(pt.x, pt.y)] {
+                let idx = tile_index(pt.x, pt.y);
+                map.revealed[idx] = true;
+            }
+        }
+    }
+}
+
+pub fn update_fog(
+    player: Query<&FieldOfView, With<Player>>,
+    mut fog: Query<(&FogTile, &mut Sprite)>,
+    map: Res<RegionMap>,
+    light: Res<LightMap>,
+) {
+    if let Ok(fov) = player.get_single() {
+        for (tile, mut sprite) in fog.iter_mut() {
+            let pt = Point::new(tile.0 % NUM_TILES_X, tile.0 / NUM_TILES_X);
+            let darkness = 1.0 - light.levels[tile.0];
+            sprite.color = if fov.fov_set.contains(&pt) {
+                // Shade by light level, leaning towards the color of the light.
+                // Actors sit underneath the fog layer, so this tints them too.
+                let tint = light.tint[tile.0];
+                let warmth = 1.0 - f32::min(tint.r(), f32::min(tint.g(), tint.b()));
+                Color::rgba(
+                    tint.r() * 0.3 * warmth,
+                    tint.g() * 0.3 * warmth,
+                    tint.b() * 0.3 * warmth,
+                    f32::max(darkness, warmth * 0.2),
+                )
+            } else if map.revealed[tile.0] {
+                Color::rgba(0.0, 0.0, 0.0, f32::max(REMEMBERED_ALPHA, darkness))
+            } else {
+                Color::BLACK
+            };
+        }
+    }
+}
+
+pub fn hide_unseen_actors(
+    player: Query<&FieldOfView, With<Player>>,
+    mut actors: Query<(&TilePosition, &mut Visibility), Without<Player>>,
+) {
+    if let Ok(fov) = player.get_single() {
+        for (pos, mut visibility) in actors.iter_mut() {
+            visibility.is_visible = fov.fov_set.contains(&Point::new(pos.x, pos.y));
+        }
+    }
+}
@@ -1,13 +1,17 @@
 use std::collections::HashSet;
 
-use crate::maps::{RegionMap, TilePosition};
+use crate::{
+    lighting::LightMap,
+    maps::{RegionMap, TilePosition},
+};
 use bevy::prelude::*;
-use bracket_pathfinding::prelude::{field_of_view_set, Point};
+use bracket_pathfinding::prelude::{field_of_view_set, DistanceAlg, Point};
 
 #[derive(Component)]
 pub struct FieldOfView {
     pub range: i32,
     pub fov_set: HashSet<Point>,
+    pub dark_vision: bool,
 }
 
 impl FieldOfView {
@@ -15,18 +19,39 @@ impl FieldOfView {
         Self {
             range,
             fov_set: HashSet::new(),
+            dark_vision: false,
         }
     }
+
+    pub fn with_dark_vision(mut self) -> Self {
+        self.dark_vision = true;
+        self
+    }
 }
 
 pub fn update_field_of_view(
-    mut fov_query: Query<
-        (&mut FieldOfView, &TilePosition),
-        Or<(Changed<TilePosition>, Added<TilePosition>)>,
-    >,
+    mut fov_query: Query<(
+        &mut FieldOfView,
+        &TilePosition,
+        ChangeTrackers<TilePosition>,
+    )>,
     map: Res<RegionMap>,
+    light: Res<LightMap>,
 ) {
-    for (mut fov, pos) in fov_query.iter_mut() {
-        fov.fov_set = field_of_view_set(Point::new(pos.x, pos.y), fov.range, &*map);
+    for (mut fov, pos, moved) in fov_query.iter_mut() {
+        // Doors opening and closing change what you can see, too
+        if !moved.is_changed() && !light.is_changed() && !map.is_changed() {
+            continue;
+        }
+        let center = Point::new(pos.x, pos.y);
+        let mut fov_set = field_of_view_set(center, fov.range, &*map);
+
+        // In the dark, you can only see lit tiles - and whatever you are bumping into
+        if !fov.dark_vision {
+            fov_set.retain(|pt| {
+                light.is_lit(pt.x, pt.y) || DistanceAlg::Pythagoras.distance2d(center, *pt) < 1.5
+            });
+        }
+        fov.fov_set = fov_set;
     }
 }
@@ -0,0 +1,128 @@
+use crate::{
+    actors::{Chicken, Henry, Player},
+    ai::{CompanionOrder, Recruited, Wanders},
+    combat::Dead,
+    console::Console,
+    factions::{Faction, Relation, Relationships},
+    items::Inventory,
+    maps::{in_coop, MapToBuild, RegionMap, TilePosition},
+    story::StoryFlags,
+    TimeStepResource,
+};
+use bevy::prelude::*;
+
+/// At most this many chickens need rounding up
+const HERD_GOAL: usize = 5;
+/// Ninety seconds, at 30 ticks a second
+const HERD_TICKS: u32 = 90 * 30;
+const GRAIN_REWARD: i32 = 5;
+
+/// The chicken round-up on Farmer Tom's farm
+#[derive(Default)]
+pub struct Herding {
+    pub active: bool,
+    pub ticks_left: u32,
+    pub goal: usize,
+    pub penned: usize,
+}
+
+type LooseChickens<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static TilePosition, Option<&'static Wanders>),
+    (With<Chicken>, Without<Recruited>, Without<Dead>),
+>;
+
+/// Telling Henry to herd starts the clock
+pub fn start_herding(
+    henry: Query<&CompanionOrder, (With<Henry>, Changed<CompanionOrder>)>,
+    chickens: LooseChickens,
+    mut herding: ResMut<Herding>,
+    story: Res<StoryFlags>,
+    console: Res<Console>,
+) {
+    if herding.active || henry.iter().all(|order| *order != CompanionOrder::Herd) {
+        return;
+    }
+    if story.chickens_herded {
+        console.write(
+            "Farmer Tom's chickens are already safe and sound.",
+            Color::GRAY,
+        );
+        return;
+    }
+    let loose = chickens
+        .iter()
+        .filter(|(_, pos, _)| !in_coop(pos.x, pos.y))
+        .count();
+    if loose == 0 {
+        console.write("There are no chickens left to round up.", Color::GRAY);
+        return;
+    }
+    *herding = Herding {
+        active: true,
+        ticks_left: HERD_TICKS,
+        goal: usize::min(loose, HERD_GOAL),
+        penned: 0,
+    };
+    console.write(
+        format!(
+            "Open the gate, and get {} chickens back in the coop before they wander off for good!",
+            herding.goal
+        ),
+        Color::YELLOW,
+    );
+}
+
+pub fn herding(
+    chickens: LooseChickens,
+    mut player: Query<&mut Inventory, With<Player>>,
+    mut herding: ResMut<Herding>,
+    mut story: ResMut<StoryFlags>,
+    mut relationships: ResMut<Relationships>,
+    map: Res<RegionMap>,
+    console: Res<Console>,
+    mut commands: Commands,
+    timer: Res<TimeStepResource>,
+) {
+    if !herding.active || !timer.timer.finished() {
+        return;
+    }
+    if map.id != MapToBuild::FarmerTomCoup {
+        herding.active = false;
+        console.write("You leave the chickens to their own devices.", Color::GRAY);
+        return;
+    }
+
+    let mut penned = 0;
+    for (chicken, pos, wanders) in chickens.iter() {
+        if in_coop(pos.x, pos.y) {
+            penned += 1;
+            // Once it's home, it settles down
+            if wanders.is_some() {
+                commands.entity(chicken).remove::<Wanders>();
+            }
+        }
+    }
+    herding.penned = penned;
+    herding.ticks_left = herding.ticks_left.saturating_sub(1);
+
+    if penned >= herding.goal {
+        herding.active = false;
+        story.chickens_herded = true;
+        relationships.set(Faction::Farmers, Faction::Player, Relation::Neutral);
+        for mut inventory in player.iter_mut() {
+            inventory.grain += GRAIN_REWARD;
+        }
+        console.write(
+            "The chickens are home! Farmer Tom leaves you a sack of grain, and stops running from you.",
+            Color::YELLOW,
+        );
+    } else if herding.ticks_left == 0 {
+        herding.active = false;
+        console.write(
+            "Time's up, and the chickens have scattered. Tell Henry to herd (7) to try again.",
+            Color::ORANGE,
+        );
+    }
+}
@@ -1,13 +1,21 @@
 use crate::{
-    actors::Player,
+    actors::{Henry, Player},
     ai::{ActionRequest, Facing},
-    combat::{Health, Hostile},
+    combat::{Dead, Health},
     console::Console,
-    maps::{LerpMove, RegionMap, TilePosition},
+    factions::{Faction, Relationships},
+    items::Inventory,
+    maps::{tile_index, LerpMove, RegionMap, TilePosition, TileType},
     random::Rng,
+    status::{Effect, StatusEffects},
 };
 use bevy::prelude::*;
-use bracket_pathfinding::prelude::{DistanceAlg, Point};
+use bracket_pathfinding::prelude::{Algorithm2D, DistanceAlg, Point};
+
+/// How much a nudge shortens Henry's nap, when you have no grain to give him
+const REVIVE_NUDGE: u32 = 60;
+/// Grain doesn't go to waste on a Henry who's still on his feet; it patches him up over time
+const FEED_REGEN_TICKS: u32 = 180;
 
 #[derive(Component)]
 pub struct Interaction {
@@ -15,19 +23,29 @@ pub struct Interaction {
 }
 
 pub fn player_interaction(
-    mut player: Query<(Entity, &Player, &TilePosition, &mut Health), Without<LerpMove>>,
+    mut player: Query<
+        (Entity, &Player, &TilePosition, &mut Health, &mut Inventory),
+        Without<LerpMove>,
+    >,
     interactions: Query<(&Interaction, &TilePosition), Without<LerpMove>>,
-    hostiles: Query<(Entity, &TilePosition), With<Hostile>>,
+    // Corpses stay on the map now, but there's no point hitting them
+    others: Query<(Entity, &TilePosition, &Faction), Without<Dead>>,
+    relationships: Res<Relationships>,
+    mut henry: Query<(&TilePosition, &Health, &mut StatusEffects), (With<Henry>, Without<Player>)>,
     keyboard: Res<Input<KeyCode>>,
-    map: Res<RegionMap>,
+    mut map: ResMut<RegionMap>,
+    mut meshes: ResMut<Assets<Mesh>>,
     console: Res<Console>,
     rng: Res<Rng>,
     mut actions: EventWriter<ActionRequest>,
 ) {
-    for (entity, player, tile_pos, mut health) in player.iter_mut() {
+    for (entity, player, tile_pos, mut health, mut inventory) in player.iter_mut() {
         if keyboard.just_pressed(KeyCode::Space) {
             let my_pt = Point::new(tile_pos.x, tile_pos.y);
-            for (hostile, hpos) in hostiles.iter() {
+            let hostiles = others
+                .iter()
+                .filter(|(_, _, faction)| relationships.is_hostile(Faction::Player, **faction));
+            for (hostile, hpos, _) in hostiles {
                 let their_pt = Point::new(hpos.x, hpos.y);
                 let distance = DistanceAlg::Pythagoras.distance2d(my_pt, their_pt);
                 if distance < 1.4 {
@@ -51,6 +69,46 @@ pub fn player_interaction(
                 Facing::Up => target.1 -= 1,
                 Facing::Down => target.1 += 1,
             }
+            let something_ahead = interactions
+                .iter()
+                .any(|(_, ipos)| (ipos.x, ipos.y) == target)
+                || (map.in_bounds(Point::new(target.0, target.1))
+                    && map.features[tile_index(target.0, target.1)] != TileType::None);
+
+            // Help Henry back up if he's been knocked out, or feed him if he's hurt. Doors and
+            // whatever else you're facing come first, unless it's Henry himself.
+            for (henry_pos, henry_health, mut effects) in henry.iter_mut() {
+                let distance =
+                    DistanceAlg::Pythagoras.distance2d(my_pt, Point::new(henry_pos.x, henry_pos.y));
+                let facing_henry = (henry_pos.x, henry_pos.y) == target;
+                if distance >= 1.5 || (something_ahead && !facing_henry) {
+                    continue;
+                }
+                if effects.has(Effect::Unconscious) {
+                    if inventory.grain > 0 {
+                        inventory.grain -= 1;
+                        effects.shorten(Effect::Unconscious, u32::MAX);
+                        console.write("Henry wolfs down the grain and leaps up!", Color::YELLOW);
+                    } else {
+                        effects.shorten(Effect::Unconscious, REVIVE_NUDGE);
+                        console.write(
+                            "You nudge Henry. He groans. Some grain would perk him up.",
+                            Color::WHITE,
+                        );
+                    }
+                    return;
+                }
+                if inventory.grain > 0 && henry_health.current < henry_health.max {
+                    inventory.grain -= 1;
+                    effects.apply(Effect::Regenerating, FEED_REGEN_TICKS);
+                    console.write("Henry gulps down the grain and perks up.", Color::YELLOW);
+                    return;
+                }
+            }
+
+            if map.use_door(target.0, target.1, &mut inventory, &console, &mut meshes) {
+                return;
+            }
             map.interact(target.0, target.1, &console, &mut health);
 
             for (interact, ipos) in interactions.iter() {
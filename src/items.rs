@@ -0,0 +1,125 @@
+use crate::{
+    actors::{Henry, Player},
+    ai::CompanionOrder,
+    combat::DeathMessage,
+    console::Console,
+    maps::{tile_index, RegionMap, TilePosition, TileType},
+    random::Rng,
+};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+#[derive(Component, Default)]
+pub struct Inventory {
+    pub keys: i32,
+    pub grain: i32,
+}
+
+/// Things that can be left lying around for the player to find
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub enum Loot {
+    Grain,
+    Key,
+}
+
+impl Loot {
+    fn tile(&self) -> TileType {
+        match self {
+            Loot::Grain => TileType::Grain,
+            Loot::Key => TileType::Key,
+        }
+    }
+}
+
+/// What an actor might drop when it dies, each with a percentage chance
+#[derive(Component)]
+pub struct LootTable(pub Vec<(Loot, i32)>);
+
+/// Where something fell, or next to it if that tile already has something on it
+fn drop_spot(map: &RegionMap, (x, y): (i32, i32)) -> Option<(i32, i32)> {
+    let around = (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| (x + dx, y + dy)));
+    std::iter::once((x, y)).chain(around).find(|(tx, ty)| {
+        map.can_player_enter(*tx, *ty) && map.features[tile_index(*tx, *ty)] == TileType::None
+    })
+}
+
+pub fn drop_loot(
+    mut deaths: EventReader<DeathMessage>,
+    tables: Query<&LootTable>,
+    mut map: ResMut<RegionMap>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    rng: Res<Rng>,
+) {
+    let mut dropped = false;
+    for death in deaths.iter() {
+        if let Ok(LootTable(table)) = tables.get(death.entity) {
+            for (loot, chance) in table.iter() {
+                if rng.range(0, 100) >= *chance {
+                    continue;
+                }
+                if let Some((x, y)) = drop_spot(&map, death.position) {
+                    map.set_feature(tile_index(x, y), loot.tile());
+                    dropped = true;
+                }
+            }
+        }
+    }
+    if dropped {
+        map.rebuild_features(&mut meshes);
+    }
+}
+
+pub fn pick_up_items(
+    mut player: Query<(&TilePosition, &mut Inventory), (With<Player>, Changed<TilePosition>)>,
+    mut map: ResMut<RegionMap>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    console: Res<Console>,
+) {
+    for (pos, mut inventory) in player.iter_mut() {
+        let idx = tile_index(pos.x, pos.y);
+        match map.features[idx] {
+            TileType::Key => {
+                inventory.keys += 1;
+                console.write("You pick up a rusty old key.", Color::YELLOW);
+            }
+            TileType::Grain => {
+                inventory.grain += 1;
+                console.write("You scoop up a handful of grain.", Color::YELLOW);
+            }
+            _ => continue,
+        }
+        map.set_feature(idx, TileType::None);
+        map.rebuild_features(&mut meshes);
+    }
+}
+
+/// Henry drops whatever he was sent to fetch straight into your pockets
+pub fn companion_fetch(
+    henry: Query<(&TilePosition, &CompanionOrder), (With<Henry>, Changed<TilePosition>)>,
+    mut player: Query<&mut Inventory, With<Player>>,
+    mut map: ResMut<RegionMap>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    console: Res<Console>,
+) {
+    for (pos, order) in henry.iter() {
+        if *order != CompanionOrder::Fetch((pos.x, pos.y)) {
+            continue;
+        }
+        if let Ok(mut inventory) = player.get_single_mut() {
+            let idx = tile_index(pos.x, pos.y);
+            match map.features[idx] {
+                TileType::Key => {
+                    inventory.keys += 1;
+                    console.write("Henry proudly brings you a rusty old key.", Color::YELLOW);
+                }
+                TileType::Grain => {
+                    inventory.grain += 1;
+                    console.write("Henry brings you a mouthful of grain.", Color::YELLOW);
+                }
+                _ => continue,
+            }
+            map.set_feature(idx, TileType::None);
+            map.rebuild_features(&mut meshes);
+        }
+    }
+}
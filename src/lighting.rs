@@ -0,0 +1,130 @@
+use crate::{
+    maps::{tile_index, RegionMap, TilePosition, NUM_TILES_X, NUM_TILES_Y},
+    TimeStepResource,
+};
+use bevy::prelude::*;
+use bracket_pathfinding::prelude::{field_of_view_set, DistanceAlg, Point};
+
+/// Below this light level, you can't see a tile unless you are standing next to it.
+pub const DARK_THRESHOLD: f32 = 0.3;
+
+/// Something that carries its own light around, like a torch.
+#[derive(Component)]
+pub struct LightSource {
+    pub radius: i32,
+    pub intensity: f32,
+    pub color: Color,
+}
+
+pub struct LightMap {
+    pub levels: Vec<f32>,
+    pub tint: Vec<Color>,
+    static_levels: Vec<f32>,
+    static_tint: Vec<Color>,
+}
+
+impl LightMap {
+    pub fn new() -> Self {
+        Self {
+            levels: vec![1.0; NUM_TILES_X * NUM_TILES_Y],
+            tint: vec![Color::WHITE; NUM_TILES_X * NUM_TILES_Y],
+            static_levels: vec![1.0; NUM_TILES_X * NUM_TILES_Y],
+            static_tint: vec![Color::WHITE; NUM_TILES_X * NUM_TILES_Y],
+        }
+    }
+
+    pub fn level(&self, x: i32, y: i32) -> f32 {
+        if x < 0 || x >= NUM_TILES_X as i32 || y < 0 || y >= NUM_TILES_Y as i32 {
+            return 0.0;
+        }
+        self.levels[tile_index(x, y)]
+    }
+
+    pub fn is_lit(&self, x: i32, y: i32) -> bool {
+        self.level(x, y) >= DARK_THRESHOLD
+    }
+}
+
+/// Light fans out from a source using the same FOV code as vision, so walls cast shadows.
+fn illuminate(
+    levels: &mut [f32],
+    tint: &mut [Color],
+    map: &RegionMap,
+    center: Point,
+    radius: i32,
+    intensity: f32,
+    color: Color,
+) {
+    for pt in field_of_view_set(center, radius, map) {
+        let distance = DistanceAlg::Pythagoras.distance2d(center, pt);
+        let contribution = intensity * (1.0 - (distance / (radius as f32 + 1.0)));
+        if contribution <= 0.0 {
+            continue;
+        }
+        let idx = tile_index(pt.x, pt.y);
+        let before = levels[idx];
+        levels[idx] = f32::min(1.0, before + contribution);
+
+        // Blend the tint by how much of the tile's light this source provides
+        let weight = contribution / (before + contribution);
+        let old = tint[idx];
+        tint[idx] = Color::rgb(
+            old.r() + (color.r() - old.r()) * weight,
+            old.g() + (color.g() - old.g()) * weight,
+            old.b() + (color.b() - old.b()) * weight,
+        );
+    }
+}
+
+pub fn update_lighting(
+    mut light: ResMut<LightMap>,
+    map: Res<RegionMap>,
+    carried: Query<(&LightSource, &TilePosition)>,
+    timer: Res<TimeStepResource>,
+) {
+    // Fixed lights only move when the map does. Checked every frame, so a change never slips by
+    // between ticks.
+    if map.is_changed() {
+        let mut levels = vec![map.ambient; NUM_TILES_X * NUM_TILES_Y];
+        let mut tint = vec![Color::WHITE; NUM_TILES_X * NUM_TILES_Y];
+        for (idx, feature) in map.features.iter().enumerate() {
+            if let Some((radius, intensity, color)) = feature.light() {
+                let center = Point::new(idx % NUM_TILES_X, idx / NUM_TILES_X);
+                illuminate(
+                    &mut levels,
+                    &mut tint,
+                    &map,
+                    center,
+                    radius,
+                    intensity,
+                    color,
+                );
+            }
+        }
+        light.static_levels = levels;
+        light.static_tint = tint;
+    }
+    if !timer.timer.finished() {
+        return;
+    }
+
+    let mut levels = light.static_levels.clone();
+    let mut tint = light.static_tint.clone();
+    for (source, pos) in carried.iter() {
+        illuminate(
+            &mut levels,
+            &mut tint,
+            &map,
+            Point::new(pos.x, pos.y),
+            source.radius,
+            source.intensity,
+            source.color,
+        );
+    }
+
+    // Only flag a change (and with it, FOV updates) if something actually moved
+    if levels != light.levels || tint != light.tint {
+        light.levels = levels;
+        light.tint = tint;
+    }
+}
@@ -1,27 +1,62 @@
 use actors::{
-    chicken_ai, henry_ai, player_movement, spawn_henry, spawn_player, spike_system,
-    unconscious_henry, Chicken, Farmer, Henry, Player, ScaresChickens, Tasty, Wolf, Spider,
+    arena_lock, henry_ai, injure_henry, player_movement, recruit_chickens, rest_henry,
+    spawn_henry, spawn_player, spike_system, update_boss_health_bar, wake_henry, white_wolf_boss,
+    Archetypes,
+};
+use ai::{
+    ambush, animate, attacks, chase_after, coordinate_packs, expire_orders, flee_from, flock,
+    flock_buffs, fly_web_shots, forage, issue_orders, note_rejections, process_actions,
+    remember_targets, search, shoot_webs, sticky_webs, think, toggle_brain_inspector,
+    topple_the_dead, travel, update_intent_labels, wander, weave_webs, ActionRejected,
+    ActionRequest, BrainInspector,
 };
-use ai::{attacks, chase_after, flee_from, process_actions, ActionRequest};
 use assets::GameAssets;
 use bevy::prelude::*;
 use combat::{
-    combat_lerp, damage_system, setup_health_hud, update_health_hud, DamageMessage, Hostile,
+    combat_lerp, damage_system, decay_corpses, setup_health_hud, update_health_hud, DamageMessage,
+    DeathMessage,
 };
 use console::{console_setup, update_consoles, Console};
+use factions::Relationships;
+use fog::{hide_unseen_actors, reveal_map, update_fog};
 use fov::update_field_of_view;
+use herding::{herding, start_herding, Herding};
 use interactions::player_interaction;
-use maps::{map_exits, tile_lerp, tile_location_added, MapToBuild, RegionMap};
+use items::{companion_fetch, drop_loot, pick_up_items};
+use lighting::{update_lighting, LightMap};
+use maps::{
+    map_exits, tile_lerp, tile_location_added, update_occupancy, MapToBuild, Occupancy, RegionMap,
+};
+use minimap::{setup_minimap, update_minimap};
+use noise::{listen, make_noise, Noise};
 use random::Rng;
+use stealth::{detect, update_awareness_indicators, update_stealth};
+use status::tick_status_effects;
+use std::time::Duration;
+use story::StoryFlags;
+use turns::{advance_turns, PlayMode, Turns};
+use world_map::{exit_world_map, open_world_map, start_world_map, world_map, WorldAtlas};
 mod actors;
 mod ai;
 mod assets;
 mod combat;
 mod console;
+mod factions;
+mod fog;
 mod fov;
+mod herding;
 mod interactions;
+mod items;
+mod lighting;
 mod maps;
+mod minimap;
+mod noise;
 mod random;
+mod stealth;
+mod status;
+mod story;
+mod turns;
+mod world_map;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum GameState {
@@ -29,16 +64,29 @@ pub enum GameState {
     Playing,
     Dead,
     Won,
+    WorldMap,
 }
 
 // Bevy has a bug! When you on_update for a specific state AND have a timestep,
 // your function runs all the time. Ugh.
 pub struct TimeStepResource {
     pub timer: Timer,
+    /// Ticks the world has run for, as a clock that stops whenever the world does
+    pub ticks: u64,
 }
 
-fn time_step_update(time: Res<Time>, mut timestep: ResMut<TimeStepResource>) {
-    timestep.timer.tick(time.delta());
+// While waiting on the player in turn-based mode, the clock stands still. It still ticks by
+// nothing, so finished() goes back to false.
+fn time_step_update(time: Res<Time>, turns: Res<Turns>, mut timestep: ResMut<TimeStepResource>) {
+    let delta = if turns.waiting {
+        Duration::ZERO
+    } else {
+        time.delta()
+    };
+    timestep.timer.tick(delta);
+    if timestep.timer.finished() {
+        timestep.ticks += 1;
+    }
 }
 
 #[derive(Component)]
@@ -78,6 +126,14 @@ fn main() {
 
     let won_step = SystemSet::on_update(GameState::Won).with_system(won_menu);
 
+    // World Map (pushed on top of Playing, so the game is paused rather than torn down)
+    let setup_world_map_step =
+        SystemSet::on_enter(GameState::WorldMap).with_system(start_world_map);
+
+    let exit_world_map_step = SystemSet::on_exit(GameState::WorldMap).with_system(exit_world_map);
+
+    let world_map_step = SystemSet::on_update(GameState::WorldMap).with_system(world_map);
+
     // Step to initialize game resources
     let setup_step = SystemSet::on_enter(GameState::Playing)
         .label("GameSetup")
@@ -90,30 +146,51 @@ fn main() {
     let input_step = SystemSet::on_update(GameState::Playing)
         .label("InputStep")
         .with_system(player_movement)
-        .with_system(player_interaction);
+        .with_system(recruit_chickens.before("Interact"))
+        .with_system(player_interaction.label("Interact"))
+        .with_system(open_world_map)
+        .with_system(toggle_brain_inspector)
+        .with_system(issue_orders);
+
+    // Every actor with a brain settles on a single intent, which the AI step then carries out
+    let think_step = SystemSet::on_update(GameState::Playing)
+        .label("ThinkStep")
+        .with_system(time_step_update.label("TimeStep"))
+        .with_system(listen.label("Listen"))
+        .with_system(update_occupancy.label("Occupancy"))
+        .with_system(note_rejections.label("Rejections"))
+        .with_system(update_stealth.label("Stealth").after("TimeStep"))
+        .with_system(detect.label("Detect").after("Stealth"))
+        .with_system(remember_targets.label("Remember").after("Detect").after("Listen"))
+        .with_system(think.label("Think").after("Remember").after("Rejections"))
+        .with_system(coordinate_packs.after("Think"))
+        .with_system(ambush.after("Think"));
 
     // The AI step handles computer-controlled actors' actions
     let ai_step = SystemSet::on_update(GameState::Playing)
         //.with_run_criteria(FixedTimestep::step(1.0 / 30.0))
         .label("AiStep")
-        .with_system(time_step_update)
+        .after("ThinkStep")
         // Running away
-        .with_system(flee_from::<Chicken, ScaresChickens>)
-        .with_system(flee_from::<Farmer, Player>)
+        .with_system(flee_from)
+        // Sticking together
+        .with_system(flock)
         // Chasing Targets
-        .with_system(chase_after::<Henry, Hostile>)
-        .with_system(chase_after::<Wolf, Tasty>)
-        .with_system(chase_after::<Spider, Tasty>)
+        .with_system(chase_after)
+        .with_system(travel)
+        .with_system(search)
         // Actor-level AI
-        .with_system(chicken_ai)
+        .with_system(wander)
         .with_system(henry_ai)
-        .with_system(unconscious_henry)
+        .with_system(wake_henry)
+        .with_system(injure_henry)
+        .with_system(rest_henry)
+        .with_system(white_wolf_boss)
+        .with_system(shoot_webs)
+        .with_system(fly_web_shots)
         // Killing things
         .with_system(spike_system)
-        .with_system(attacks::<Wolf, Tasty>)
-        .with_system(attacks::<Spider, Tasty>)
-        .with_system(attacks::<Henry, Hostile>)
-        .with_system(attacks::<Player, Hostile>); // Auto attack mode
+        .with_system(attacks); // Includes the player's auto attack mode
 
     let action_step = SystemSet::on_update(GameState::Playing)
         .label("ActionStep")
@@ -124,12 +201,37 @@ fn main() {
         //.with_run_criteria(FixedTimestep::step(1.0 / 30.0))
         .with_system(combat_lerp)
         .with_system(tile_lerp)
+        .with_system(update_lighting)
         .with_system(update_field_of_view);
 
     let cleanup_step = SystemSet::on_update(GameState::Playing)
         .with_system(tile_location_added)
         .with_system(update_consoles)
         .with_system(update_health_hud)
+        .with_system(update_minimap)
+        .with_system(pick_up_items)
+        .with_system(companion_fetch)
+        .with_system(make_noise)
+        .with_system(expire_orders)
+        .with_system(reveal_map)
+        .with_system(update_fog)
+        .with_system(hide_unseen_actors)
+        .with_system(update_intent_labels)
+        .with_system(update_awareness_indicators)
+        .with_system(advance_turns)
+        .with_system(animate)
+        .with_system(topple_the_dead)
+        .with_system(decay_corpses)
+        .with_system(drop_loot)
+        .with_system(flock_buffs)
+        .with_system(forage)
+        .with_system(start_herding)
+        .with_system(herding)
+        .with_system(tick_status_effects)
+        .with_system(weave_webs)
+        .with_system(sticky_webs)
+        .with_system(arena_lock)
+        .with_system(update_boss_health_bar)
         .label("Cleanup");
 
     let migrate_step = SystemSet::on_update(GameState::Playing)
@@ -146,11 +248,19 @@ fn main() {
         })
         .insert_resource(TimeStepResource {
             timer: Timer::from_seconds(1.0 / 30.0, true),
+            ticks: 0,
         })
         .add_plugins(DefaultPlugins)
         .add_state(GameState::MainMenu)
         .add_event::<ActionRequest>()
+        .add_event::<ActionRejected>()
         .add_event::<DamageMessage>()
+        .add_event::<DeathMessage>()
+        .add_event::<Noise>()
+        .init_resource::<StoryFlags>()
+        .init_resource::<Herding>()
+        .init_resource::<BrainInspector>()
+        .init_resource::<Turns>()
         .add_startup_system(setup)
         // Main Menu
         .add_system_set(setup_menu_step)
@@ -164,6 +274,10 @@ fn main() {
         .add_system_set(setup_won_step)
         .add_system_set(exit_won_step)
         .add_system_set(won_step)
+        // World Map
+        .add_system_set(setup_world_map_step)
+        .add_system_set(exit_world_map_step)
+        .add_system_set(world_map_step)
         // Game Initialization
         .add_system_set(setup_step)
         .add_system_set(game_over_step)
@@ -171,6 +285,7 @@ fn main() {
         // It just emits messages, nothing changes
         .add_stage("DecisionStage", SystemStage::parallel())
         .add_system_set(input_step)
+        .add_system_set(think_step)
         .add_system_set(ai_step)
         // The ActionStage processes requested actions and coalesces them into a final
         // decision
@@ -183,14 +298,14 @@ fn main() {
         .add_stage_after("LerpStage", "CleanupStage", SystemStage::single_threaded())
         .add_system_set(cleanup_step)
         // The battle system runs next-to-last, since it can delete things
+        .add_stage_after("CleanupStage", "battle", SystemStage::single_threaded())
+        .add_system(damage_system)
+        // A final stage for migrating between maps
         .add_stage_after(
-            "CleanupStage",
-            "battle",
+            CoreStage::Update,
+            "migration",
             SystemStage::single_threaded(),
         )
-        .add_system(damage_system)
-        // A final stage for migrating between maps
-        .add_stage_after(CoreStage::Update, "migration", SystemStage::single_threaded())
         .add_system_set(migrate_step)
         .run();
 }
@@ -214,22 +329,30 @@ fn setup(
     // Resources
     commands.insert_resource(assets);
     commands.insert_resource(rng);
+    commands.insert_resource(Archetypes::load());
 }
 
 fn setup_game(
     mut commands: Commands,
     assets: Res<GameAssets>,
+    archetypes: Res<Archetypes>,
     rng: Res<Rng>,
+    mut turns: ResMut<Turns>,
     mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
 ) {
     // Console
     let console = Console::new();
     console_setup(&assets, &mut commands, &console);
+    if turns.is_turn_based() {
+        console.write("Turn-based: the world waits for you to move.", Color::CYAN);
+    }
+    turns.start_turn();
 
     // Spawn a map
     let mut region_map = RegionMap::new(MapToBuild::FarmerTomCoup, &rng);
     //let mut region_map = RegionMap::new(MapToBuild::Forest, &rng);
-    region_map.spawn(&assets, &mut meshes, &mut commands);
+    region_map.spawn(&assets, &archetypes, &mut meshes, &mut commands);
 
     // Spawn the player
     spawn_player(&mut commands, &assets, region_map.player_start);
@@ -239,8 +362,16 @@ fn setup_game(
 
     // HUD stuff
     setup_health_hud(&mut commands, &assets);
+    setup_minimap(&mut commands, &mut images);
 
     // Resources
+    commands.insert_resource(WorldAtlas::new(&region_map));
+    commands.insert_resource(LightMap::new());
+    commands.insert_resource(Occupancy::new());
+    commands.insert_resource(StoryFlags::default());
+    commands.insert_resource(Herding::default());
+    // Loaded fresh each game, since the story can change how factions feel
+    commands.insert_resource(Relationships::load());
     commands.insert_resource(console);
     commands.insert_resource(region_map);
 }
@@ -255,12 +386,40 @@ fn start_main_menu(mut commands: Commands, assets: Res<GameAssets>) {
             ..default()
         })
         .insert(MainMenu);
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                "Press T instead to play turn-based",
+                TextStyle {
+                    font: assets.font.clone(),
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    horizontal: HorizontalAlign::Center,
+                    vertical: VerticalAlign::Center,
+                },
+            ),
+            transform: Transform::from_xyz(0.0, -768.0 / 2.0 + 24.0, 1.0),
+            ..default()
+        })
+        .insert(MainMenu);
 }
 
-fn main_menu(keyboard: Res<Input<KeyCode>>, mut state: ResMut<State<GameState>>) {
-    if keyboard.just_pressed(KeyCode::P) {
-        state.set(GameState::Playing).unwrap();
-    }
+fn main_menu(
+    keyboard: Res<Input<KeyCode>>,
+    mut state: ResMut<State<GameState>>,
+    mut turns: ResMut<Turns>,
+) {
+    let mode = if keyboard.just_pressed(KeyCode::P) {
+        PlayMode::RealTime
+    } else if keyboard.just_pressed(KeyCode::T) {
+        PlayMode::TurnBased
+    } else {
+        return;
+    };
+    *turns = Turns::new(mode);
+    state.set(GameState::Playing).unwrap();
 }
 
 fn exit_main_menu(mut commands: Commands, query: Query<Entity, With<MainMenu>>) {
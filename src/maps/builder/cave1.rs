@@ -13,6 +13,7 @@ pub fn build(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer {
     let features = vec![TileType::None; NUM_TILES_X * NUM_TILES_Y];
     let mut exits = Vec::new();
     let mut spawns = Vec::new();
+    let exit_conditions = Vec::new();
     let player_start = if let Some(from) = from {
         match from {
             MapToBuild::Cave2 => (3, 1),
@@ -87,6 +88,8 @@ pub fn build(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer {
         player_start,
         exits,
         spawns,
+        ambient: 0.1,
+        exit_conditions,
     }
 }
 
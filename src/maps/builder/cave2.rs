@@ -3,15 +3,28 @@ use super::{
     MapToBuild, MapTransfer,
 };
 use crate::{
-    maps::{tile_index, TileType, NUM_TILES_X, NUM_TILES_Y},
+    maps::{tile_index, ExitCondition, TileType, NUM_TILES_X, NUM_TILES_Y},
     random::Rng,
 };
 
+/// The White Wolf's den, corner to corner
+const ARENA: (i32, i32, i32, i32) = (10, 5, 24, 11);
+
+/// Two tiles deep at each way in and out of the den, so there's no jumping them once they burn.
+/// The last two stop anyone leaping the channel back to the gauntlet, whose middle row is
+/// already alight.
+pub const ARENA_GATES: [(i32, i32); 6] = [(8, 5), (9, 5), (25, 8), (26, 8), (8, 9), (8, 11)];
+
+pub fn in_arena(x: i32, y: i32) -> bool {
+    x >= ARENA.0 && x <= ARENA.2 && y >= ARENA.1 && y <= ARENA.3
+}
+
 pub fn build(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer {
     let mut tiles = vec![TileType::Water; NUM_TILES_X * NUM_TILES_Y];
     let mut features = vec![TileType::None; NUM_TILES_X * NUM_TILES_Y];
     let mut exits = Vec::new();
     let mut spawns = Vec::new();
+    let mut exit_conditions = Vec::new();
     let player_start = if let Some(from) = from {
         match from {
             _ => (4, NUM_TILES_Y as i32 - 2),
@@ -20,7 +33,7 @@ pub fn build(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer {
         (4, NUM_TILES_Y as i32 - 2)
     };
 
-    for y in NUM_TILES_Y as i32 - 3..NUM_TILES_Y as i32 - 1 {
+    for y in NUM_TILES_Y as i32 - 3..NUM_TILES_Y as i32 {
         for x in 3..=5 {
             let idx = tile_index(x, y);
             tiles[idx] = TileType::CaveFloor;
@@ -29,6 +42,7 @@ pub fn build(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer {
             }
         }
     }
+    exit_conditions.push((2, ExitCondition::WhiteWolfDefeated));
 
     for y in 9..NUM_TILES_Y as i32 - 3 {
         for x in 2..=8 {
@@ -66,8 +80,8 @@ pub fn build(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer {
     for x in 4..11 {
         tiles[tile_index(x, 5)] = TileType::CaveFloor;
     }
-    for y in 5..12 {
-        for x in 10..25 {
+    for y in ARENA.1..=ARENA.3 {
+        for x in ARENA.0..=ARENA.2 {
             tiles[tile_index(x, y)] = TileType::CaveFloor;
         }
     }
@@ -87,5 +101,7 @@ pub fn build(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer {
         player_start,
         exits,
         spawns,
+        ambient: 0.15,
+        exit_conditions,
     }
 }
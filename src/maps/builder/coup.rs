@@ -5,11 +5,24 @@ use crate::{
 };
 use bracket_pathfinding::prelude::{DistanceAlg, Point, Rect};
 
+/// The fenced pen, from corner post to corner post
+const COOP: (i32, i32, i32, i32) = (11, 7, 20, 12);
+
+/// Inside the coop's fence
+pub fn in_coop(x: i32, y: i32) -> bool {
+    x > COOP.0 && x < COOP.2 && y > COOP.1 && y < COOP.3
+}
+
+pub fn coop_center() -> (i32, i32) {
+    ((COOP.0 + COOP.2) / 2, (COOP.1 + COOP.3) / 2)
+}
+
 pub fn build_farmer_tom_coup(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer {
     let mut tiles = vec![TileType::Grass; NUM_TILES_X * NUM_TILES_Y];
     let mut features = vec![TileType::None; NUM_TILES_X * NUM_TILES_Y];
     let mut exits = Vec::new();
     let mut spawns = Vec::new();
+    let exit_conditions = Vec::new();
 
     let player_start = if let Some(_from) = from {
         (17i32, 0i32)
@@ -18,17 +31,20 @@ pub fn build_farmer_tom_coup(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer
     };
 
     // Coup
-    for x in 11..21 {
-        for y in 7..13 {
+    for x in COOP.0..=COOP.2 {
+        for y in COOP.1..=COOP.3 {
             tiles[tile_index(x, y)] = TileType::Dirt;
-            if y == 7 || y == 12 {
+            if y == COOP.1 || y == COOP.3 {
                 features[tile_index(x, y)] = TileType::FenceHorizontal;
-            } else if x == 11 || x == 20 {
+            } else if x == COOP.0 || x == COOP.2 {
                 features[tile_index(x, y)] = TileType::FenceVertical;
             }
         }
     }
 
+    // Gate out to the road
+    features[tile_index(16, 7)] = TileType::DoorClosed;
+
     // Cauldron
     features[tile_index(13, 10)] = TileType::Cauldron;
 
@@ -55,7 +71,7 @@ pub fn build_farmer_tom_coup(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer
     }
 
     // Cull unreachable
-    let coup = Rect::with_exact(11, 7, 21, 13);
+    let coup = Rect::with_exact(COOP.0, COOP.1, COOP.2 + 1, COOP.3 + 1);
     for idx in unreachable(&tiles, &features, &vec![tile_index(15, 14)]) {
         if !coup.point_in_rect(Point::new(idx % NUM_TILES_X, idx / NUM_TILES_X)) {
             features[idx] = TileType::Bush;
@@ -119,5 +135,7 @@ pub fn build_farmer_tom_coup(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer
         player_start,
         exits,
         spawns,
+        ambient: 1.0,
+        exit_conditions,
     }
 }
@@ -11,6 +11,7 @@ pub fn build(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer {
     let mut features = vec![TileType::None; NUM_TILES_X * NUM_TILES_Y];
     let mut exits = Vec::new();
     let mut spawns = Vec::new();
+    let exit_conditions = Vec::new();
     let player_start = if let Some(from) = from {
         match from {
             MapToBuild::Cave1 => (27, 0),
@@ -104,6 +105,10 @@ pub fn build(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer {
         open_spots.remove(spot_index);
     }
 
+    // The cave is behind a locked door
+    features[tile_index(27, 2)] = TileType::DoorLocked;
+    spawns.retain(|(_, x, y)| *x != 27 || *y != 2);
+
     MapTransfer {
         tiles,
         features,
@@ -111,5 +116,7 @@ pub fn build(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer {
         player_start,
         exits,
         spawns,
+        ambient: 0.7,
+        exit_conditions,
     }
 }
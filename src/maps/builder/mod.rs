@@ -1,4 +1,4 @@
-use super::{tile_index, TileType};
+use super::{tile_index, ExitCondition, TileType};
 use crate::random::Rng;
 mod cave1;
 mod cave2;
@@ -8,7 +8,7 @@ mod forest;
 mod unreachable;
 mod utils;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum MapToBuild {
     FarmerTomCoup,
     FarmHouse,
@@ -17,6 +17,19 @@ pub enum MapToBuild {
     Forest,
 }
 
+impl MapToBuild {
+    /// Exits refer to their destination by number
+    pub fn from_exit(exit: usize) -> Self {
+        match exit {
+            1 => MapToBuild::FarmHouse,
+            2 => MapToBuild::Cave1,
+            3 => MapToBuild::Cave2,
+            4 => MapToBuild::Forest,
+            _ => MapToBuild::FarmerTomCoup,
+        }
+    }
+}
+
 pub struct MapTransfer {
     pub tiles: Vec<TileType>,
     pub features: Vec<TileType>,
@@ -24,8 +37,13 @@ pub struct MapTransfer {
     pub player_start: (i32, i32),
     pub exits: Vec<(usize, usize)>,
     pub spawns: Vec<(String, i32, i32)>,
+    pub ambient: f32,
+    pub exit_conditions: Vec<(usize, ExitCondition)>,
 }
 
+pub use cave2::{in_arena, ARENA_GATES};
+pub use coup::{coop_center, in_coop};
+
 pub fn builder(map: MapToBuild, rng: &Rng, from: Option<MapToBuild>) -> MapTransfer {
     match map {
         MapToBuild::FarmerTomCoup => coup::build_farmer_tom_coup(rng, from),
@@ -1,6 +1,6 @@
 use super::{spawn_big_feature, MapToBuild, MapTransfer};
 use crate::{
-    maps::{tile_index, TileType, NUM_TILES_X, NUM_TILES_Y},
+    maps::{tile_index, ExitCondition, TileType, NUM_TILES_X, NUM_TILES_Y},
     random::Rng,
 };
 use bracket_pathfinding::prelude::{DistanceAlg, Point};
@@ -10,6 +10,7 @@ pub fn build_toms_house(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer {
     let mut features = vec![TileType::None; NUM_TILES_X * NUM_TILES_Y];
     let mut exits = Vec::new();
     let mut spawns = Vec::new();
+    let mut exit_conditions = Vec::new();
     let player_start = if let Some(from) = from {
         match from {
             MapToBuild::Cave1 => (28, 3),
@@ -68,8 +69,9 @@ pub fn build_toms_house(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer {
     tiles[tile_index(13, 14)] = TileType::CobbleBL;
     tiles[tile_index(24, 14)] = TileType::CobbleBR;
 
-    // Anvil
+    // Anvil, and the key to the woods
     features[tile_index(23, 13)] = TileType::Anvil;
+    features[tile_index(21, 12)] = TileType::Key;
 
     // Add a haycart
     spawn_big_feature(10, 5, TileType::HayCart, &mut features);
@@ -86,6 +88,7 @@ pub fn build_toms_house(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer {
         features[idx] = TileType::None;
         exits.push((idx, 4));
     }
+    exit_conditions.push((4, ExitCondition::HasKey));
 
     // Add some pretty flowers and chickens
     tiles.iter_mut().enumerate().for_each(|(idx, t)| {
@@ -137,5 +140,7 @@ pub fn build_toms_house(rng: &Rng, from: Option<MapToBuild>) -> MapTransfer {
         player_start,
         exits,
         spawns,
+        ambient: 1.0,
+        exit_conditions,
     }
 }
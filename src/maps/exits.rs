@@ -1,39 +1,81 @@
 use super::{tile_index, LerpMove, MapElement, RegionMap, TilePosition};
 use crate::{
-    actors::{Henry, Player},
-    ai::ActionRequest,
+    actors::{Archetypes, Henry, Player},
+    ai::{ActionRequest, Recruited},
     assets::GameAssets,
-    combat::DamageMessage,
+    combat::{DamageMessage, Dead},
+    console::Console,
+    items::Inventory,
     random::Rng,
+    story::StoryFlags,
+    world_map::WorldAtlas,
 };
 use bevy::{ecs::event::Events, prelude::*};
 
+#[derive(Clone, Copy)]
+pub enum ExitCondition {
+    HasKey,
+    WhiteWolfDefeated,
+}
+
+impl ExitCondition {
+    fn is_met(&self, inventory: &Inventory, story: &StoryFlags) -> bool {
+        match self {
+            ExitCondition::HasKey => inventory.keys > 0,
+            ExitCondition::WhiteWolfDefeated => story.white_wolf_defeated,
+        }
+    }
+
+    fn describe(&self) -> &'static str {
+        match self {
+            ExitCondition::HasKey => "The gate is padlocked. Farmer Tom must keep a key somewhere.",
+            ExitCondition::WhiteWolfDefeated => {
+                "The White Wolf's howl echoes behind you. There's no turning back now!"
+            }
+        }
+    }
+}
+
 pub fn map_exits(
     mut map: ResMut<RegionMap>,
     mut queries: ParamSet<(
-        Query<&TilePosition, (With<Player>, Changed<TilePosition>)>,
+        Query<(&TilePosition, &Inventory), (With<Player>, Changed<TilePosition>)>,
         Query<Entity, With<MapElement>>,
         Query<(&Player, &mut TilePosition)>,
         Query<(Entity, &mut TilePosition), With<Henry>>,
+        Query<(Entity, &mut TilePosition, Option<&Dead>), With<Recruited>>,
     )>,
     mut commands: Commands,
     assets: Res<GameAssets>,
+    archetypes: Res<Archetypes>,
     mut meshes: ResMut<Assets<Mesh>>,
     rng: Res<Rng>,
     mut events: ResMut<Events<ActionRequest>>,
     mut damage: ResMut<Events<DamageMessage>>,
+    mut atlas: ResMut<WorldAtlas>,
+    story: Res<StoryFlags>,
+    console: Res<Console>,
 ) {
     let mut transition = None;
-    for player_pos in queries.p0().iter() {
+    for (player_pos, inventory) in queries.p0().iter() {
         let player_idx = tile_index(player_pos.x, player_pos.y);
         for (exit, new_map) in map.exits.iter() {
             if *exit == player_idx {
-                transition = Some(*new_map);
+                let blocked = map.exit_conditions.iter().find(|(destination, condition)| {
+                    destination == new_map && !condition.is_met(inventory, &story)
+                });
+                if let Some((_, condition)) = blocked {
+                    console.write(condition.describe(), Color::ORANGE);
+                } else {
+                    transition = Some(*new_map);
+                }
             }
         }
     }
 
     if let Some(new_map) = transition {
+        map.unlock_exit(new_map);
+
         // Clear the events queue
         events.update();
         damage.update();
@@ -47,9 +89,11 @@ pub fn map_exits(
             &mut commands,
             &queries.p1(),
             &assets,
+            &archetypes,
             &mut meshes,
             &rng,
         );
+        atlas.visit(&map);
 
         // Adjust player position
         let mut player_pos = (0, 0);
@@ -64,6 +108,26 @@ pub fn map_exits(
             commands.entity(henry).remove::<LerpMove>();
         }
 
+        // The flock crowds in around you, nearest free spots first
+        let mut taken = vec![player_pos, (player_pos.0 - 1, player_pos.1)];
+        let mut spots: Vec<(i32, i32)> = (-2..=2)
+            .flat_map(|dy| (-2..=2).map(move |dx| (player_pos.0 + dx, player_pos.1 + dy)))
+            .filter(|(x, y)| map.can_player_enter(*x, *y))
+            .collect();
+        spots.sort_by_key(|(x, y)| (x - player_pos.0).abs() + (y - player_pos.1).abs());
+        for (chicken, mut chicken_pos, dead) in queries.p4().iter_mut() {
+            if dead.is_some() {
+                commands.entity(chicken).despawn();
+                continue;
+            }
+            let spot = spots.iter().copied().find(|spot| !taken.contains(spot));
+            let (x, y) = spot.unwrap_or(player_pos);
+            taken.push((x, y));
+            chicken_pos.x = x;
+            chicken_pos.y = y;
+            commands.entity(chicken).remove::<LerpMove>();
+        }
+
         events.clear();
         damage.clear();
         events.update();
@@ -1,4 +1,7 @@
-use crate::TimeStepResource;
+use crate::{
+    status::{Effect, StatusEffects},
+    TimeStepResource,
+};
 
 use super::{tile_to_screen, TilePosition};
 use bevy::prelude::*;
@@ -9,7 +12,8 @@ pub struct LerpMove {
     pub end: (i32, i32),
     pub step: u32,
     pub jumping: bool,
-    pub animate: Option<Vec<usize>>,
+    /// Bumps head toward the end, and bounce back to the start
+    pub bump: bool,
 }
 
 pub fn tile_location_added(
@@ -30,7 +34,7 @@ pub fn tile_lerp(
         &mut LerpMove,
         &mut TilePosition,
         &mut Transform,
-        Option<&mut TextureAtlasSprite>,
+        Option<&StatusEffects>,
     )>,
     mut commands: Commands,
     timer: Res<TimeStepResource>,
@@ -38,22 +42,21 @@ pub fn tile_lerp(
     if !timer.timer.finished() {
         return;
     }
-    for (entity, mut lerp, mut pos, mut trans, mut sprite) in query.iter_mut() {
-        lerp.step += 1;
+    for (entity, mut lerp, mut pos, mut trans, effects) in query.iter_mut() {
+        let hasted = effects.is_some_and(|effects| effects.has(Effect::Hasted));
+        lerp.step += if hasted { 2 } else { 1 };
 
         let start = tile_to_screen(lerp.start.0, lerp.start.1);
         let end = tile_to_screen(lerp.end.0, lerp.end.1);
         let step = ((end.0 - start.0) / 8.0, (end.1 - start.1) / 8.0);
+        let progress = if lerp.bump {
+            u32::min(lerp.step, 8 - u32::min(lerp.step, 8)) as f32 / 2.0
+        } else {
+            lerp.step as f32
+        };
 
-        trans.translation.x = start.0 + (step.0 * lerp.step as f32);
-        trans.translation.y = start.1 + (step.1 * lerp.step as f32);
-
-        if let Some(animate) = &lerp.animate {
-            let frame = lerp.step % animate.len() as u32;
-            if let Some(sprite) = &mut sprite {
-                sprite.index = animate[frame as usize];
-            }
-        }
+        trans.translation.x = start.0 + (step.0 * progress);
+        trans.translation.y = start.1 + (step.1 * progress);
 
         if lerp.jumping {
             match lerp.step {
@@ -70,13 +73,10 @@ pub fn tile_lerp(
 
         // Finish the move
         if lerp.step > 8 {
-            if let Some(animate) = &lerp.animate {
-                if let Some(sprite) = &mut sprite {
-                    sprite.index = animate[0];
-                }
+            if !lerp.bump {
+                pos.x = lerp.end.0;
+                pos.y = lerp.end.1;
             }
-            pos.x = lerp.end.0;
-            pos.y = lerp.end.1;
             let tts = tile_to_screen(pos.x, pos.y);
             trans.translation = Vec3::new(tts.0, tts.1, trans.translation.z);
             commands.entity(entity).remove::<LerpMove>();
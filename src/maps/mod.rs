@@ -1,6 +1,8 @@
 mod builder;
 mod exits;
 mod lerp_move;
+mod navigation;
+mod occupancy;
 mod position;
 mod region_map;
 mod tile_type;
@@ -8,6 +10,8 @@ mod tilemap;
 pub use builder::*;
 pub use exits::*;
 pub use lerp_move::*;
+pub use navigation::*;
+pub use occupancy::*;
 pub use position::*;
 pub use region_map::*;
 pub use tile_type::*;
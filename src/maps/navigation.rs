@@ -0,0 +1,100 @@
+use super::{tile_index, Occupancy, RegionMap, NUM_TILES_X, NUM_TILES_Y};
+use bevy::prelude::Entity;
+use bracket_pathfinding::prelude::{
+    a_star_search, Algorithm2D, BaseMap, DistanceAlg, Point, SmallVec,
+};
+
+/// Jumping takes a bit more effort than walking, so it's only used when it saves steps
+const JUMP_COST: f32 = 2.5;
+/// Squeezing past another actor is possible, but only worth it if there's no other way
+const OCCUPIED_COST: f32 = 8.0;
+
+/// A view of the region for pathfinding: the same walkable tiles as the map, optionally
+/// with 2-tile leaps over anything low enough to clear, and with other actors in the way.
+pub struct Navigator<'a> {
+    map: &'a RegionMap,
+    jumps: bool,
+    occupancy: Option<(&'a Occupancy, Entity)>,
+}
+
+impl<'a> Navigator<'a> {
+    pub fn new(map: &'a RegionMap) -> Self {
+        Self {
+            map,
+            jumps: false,
+            occupancy: None,
+        }
+    }
+
+    pub fn with_jumps(mut self) -> Self {
+        self.jumps = true;
+        self
+    }
+
+    /// Steer `me` around everyone else
+    pub fn avoiding(mut self, occupancy: &'a Occupancy, me: Entity) -> Self {
+        self.occupancy = Some((occupancy, me));
+        self
+    }
+
+    fn step_cost(&self, idx: usize, cost: f32) -> f32 {
+        let x = (idx % NUM_TILES_X) as i32;
+        let y = (idx / NUM_TILES_X) as i32;
+        match self.occupancy {
+            Some((occupancy, me)) if occupancy.is_blocked(x, y, me) => cost + OCCUPIED_COST,
+            _ => cost,
+        }
+    }
+
+    /// Returns the tiles to step through (not including the start), or None if there is no way.
+    pub fn path(&self, from: (i32, i32), to: (i32, i32)) -> Option<Vec<(i32, i32)>> {
+        let path = a_star_search(tile_index(from.0, from.1), tile_index(to.0, to.1), self);
+        if !path.success {
+            return None;
+        }
+        Some(
+            path.steps
+                .iter()
+                .skip(1)
+                .map(|idx| ((idx % NUM_TILES_X) as i32, (idx / NUM_TILES_X) as i32))
+                .collect(),
+        )
+    }
+}
+
+impl<'a> Algorithm2D for Navigator<'a> {
+    fn dimensions(&self) -> Point {
+        Point::new(NUM_TILES_X, NUM_TILES_Y)
+    }
+
+    fn in_bounds(&self, pos: Point) -> bool {
+        self.map.in_bounds(pos)
+    }
+}
+
+impl<'a> BaseMap for Navigator<'a> {
+    fn is_opaque(&self, idx: usize) -> bool {
+        self.map.is_opaque(idx)
+    }
+
+    fn get_pathing_distance(&self, idx1: usize, idx2: usize) -> f32 {
+        DistanceAlg::Pythagoras.distance2d(self.index_to_point2d(idx1), self.index_to_point2d(idx2))
+    }
+
+    fn get_available_exits(&self, idx: usize) -> SmallVec<[(usize, f32); 10]> {
+        let mut exits = self.map.get_available_exits(idx);
+        if self.jumps {
+            let here = self.index_to_point2d(idx);
+            for (dx, dy) in [(-2, 0), (2, 0), (0, -2), (0, 2)] {
+                let there = (here.x + dx, here.y + dy);
+                if self.map.can_jump((here.x, here.y), there) {
+                    exits.push((tile_index(there.0, there.1), JUMP_COST));
+                }
+            }
+        }
+        exits
+            .into_iter()
+            .map(|(exit, cost)| (exit, self.step_cost(exit, cost)))
+            .collect()
+    }
+}
@@ -0,0 +1,51 @@
+use super::{tile_index, LerpMove, TilePosition, NUM_TILES_X, NUM_TILES_Y};
+use crate::{combat::Dead, factions::Faction};
+use bevy::prelude::*;
+
+/// Which actor is standing on (or moving into) each tile, rebuilt every frame
+pub struct Occupancy {
+    tiles: Vec<Option<Entity>>,
+}
+
+impl Occupancy {
+    pub fn new() -> Self {
+        Self {
+            tiles: vec![None; NUM_TILES_X * NUM_TILES_Y],
+        }
+    }
+
+    pub fn occupant(&self, x: i32, y: i32) -> Option<Entity> {
+        if x < 0 || x >= NUM_TILES_X as i32 || y < 0 || y >= NUM_TILES_Y as i32 {
+            return None;
+        }
+        self.tiles[tile_index(x, y)]
+    }
+
+    /// Is somebody other than `me` in the way?
+    pub fn is_blocked(&self, x: i32, y: i32, me: Entity) -> bool {
+        matches!(self.occupant(x, y), Some(other) if other != me)
+    }
+
+    fn claim(&mut self, x: i32, y: i32, entity: Entity) {
+        if x >= 0 && x < NUM_TILES_X as i32 && y >= 0 && y < NUM_TILES_Y as i32 {
+            self.tiles[tile_index(x, y)] = Some(entity);
+        }
+    }
+}
+
+pub fn update_occupancy(
+    mut occupancy: ResMut<Occupancy>,
+    actors: Query<(Entity, &TilePosition, Option<&LerpMove>), (With<Faction>, Without<Dead>)>,
+) {
+    let mut fresh = Occupancy::new();
+    for (entity, pos, lerp) in actors.iter() {
+        fresh.claim(pos.x, pos.y, entity);
+        // Somebody halfway into a tile already has dibs on it
+        if let Some(lerp) = lerp {
+            if !lerp.bump {
+                fresh.claim(lerp.end.0, lerp.end.1, entity);
+            }
+        }
+    }
+    occupancy.tiles = fresh.tiles;
+}
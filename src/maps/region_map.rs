@@ -1,18 +1,25 @@
-use super::{builder, tile_index, MapToBuild, TileMapLayer, TileType, NUM_TILES_X, NUM_TILES_Y};
+use super::{
+    builder, tile_index, ExitCondition, MapToBuild, TileMapLayer, TileType, NUM_TILES_X,
+    NUM_TILES_Y,
+};
 use crate::{
-    actors::{spawn_chicken, spawn_farmer, spawn_mommy_wolf, spawn_spikes, spawn_wolf, spawn_spider},
+    actors::{spawn_actor, spawn_spikes, Archetypes},
     assets::GameAssets,
     combat::Health,
     console::Console,
+    fog::spawn_fog,
+    items::Inventory,
     random::Rng,
     GameElement,
 };
-use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle, utils::HashMap};
 use bracket_pathfinding::prelude::{Algorithm2D, BaseMap, DistanceAlg, Point, SmallVec};
 
 #[derive(Component)]
 pub struct MapElement;
 
+pub const MAX_JUMP_HEIGHT: u8 = 1;
+
 pub struct RegionMap {
     pub name: String,
     pub base_tiles: Vec<TileType>,
@@ -21,8 +28,17 @@ pub struct RegionMap {
     pub player_start: (i32, i32),
     pub mesh2: Option<Handle<Mesh>>,
     pub exits: Vec<(usize, usize)>,
+    pub exit_conditions: Vec<(usize, ExitCondition)>,
     pub spawns: Vec<(String, i32, i32)>,
     pub id: MapToBuild,
+    pub revealed: Vec<bool>,
+    /// What was revealed in every other region visited, for when the player comes back
+    explored: HashMap<MapToBuild, Vec<bool>>,
+    pub ambient: f32,
+    /// Features the player changed for good (keys taken, doors unlocked), since regions are rebuilt
+    changed_features: Vec<(MapToBuild, usize, TileType)>,
+    /// Conditional exits the player has passed through, by region and destination
+    unlocked_exits: Vec<(MapToBuild, usize)>,
 }
 
 impl RegionMap {
@@ -35,29 +51,35 @@ impl RegionMap {
             features: map.features,
             player_start: map.player_start,
             exits: map.exits,
+            exit_conditions: map.exit_conditions,
             mesh: None,
             mesh2: None,
             spawns: map.spawns,
             id: map_type,
+            revealed: vec![false; NUM_TILES_X * NUM_TILES_Y],
+            explored: HashMap::new(),
+            ambient: map.ambient,
+            changed_features: Vec::new(),
+            unlocked_exits: Vec::new(),
         }
     }
 
     pub fn spawn(
         &mut self,
         assets: &GameAssets,
+        archetypes: &Archetypes,
         meshes: &mut Assets<Mesh>,
         commands: &mut Commands,
     ) {
         for (tag, x, y) in self.spawns.iter() {
             match tag.as_str() {
-                "Chicken" => spawn_chicken(*x, *y, assets, commands),
-                "Farmer" => spawn_farmer(*x, *y, assets, commands),
-                "WeakWolf" => spawn_wolf(*x, *y, 1, assets, commands),
-                "WhiteWolf" => spawn_mommy_wolf(*x, *y, 35, assets, commands),
                 "Spikes1" => spawn_spikes(*x, *y, false, assets, commands),
                 "Spikes2" => spawn_spikes(*x, *y, true, assets, commands),
-                "Spider" => spawn_spider(*x, *y, assets, commands),
-                _ => println!("Warning: Don't know how to spawn a [{tag}]"),
+                _ => {
+                    if !spawn_actor(tag, *x, *y, archetypes, assets, commands) {
+                        println!("Warning: Don't know how to spawn a [{tag}]");
+                    }
+                }
             }
         }
 
@@ -85,6 +107,8 @@ impl RegionMap {
             })
             .insert(MapElement);
 
+        spawn_fog(commands);
+
         // Label
         commands
             .spawn_bundle(TextBundle {
@@ -124,6 +148,7 @@ impl RegionMap {
         commands: &mut Commands,
         elements: &Query<Entity, With<MapElement>>,
         assets: &GameAssets,
+        archetypes: &Archetypes,
         meshes: &mut Assets<Mesh>,
         rng: &Rng,
     ) -> Point {
@@ -131,22 +156,32 @@ impl RegionMap {
         elements.for_each(|e| commands.entity(e).despawn());
 
         // Build a map
-        let to_build = match new_map {
-            1 => MapToBuild::FarmHouse,
-            2 => MapToBuild::Cave1,
-            3 => MapToBuild::Cave2,
-            4 => MapToBuild::Forest,
-            _ => MapToBuild::FarmerTomCoup,
-        };
+        let to_build = MapToBuild::from_exit(new_map);
         let new_data = builder(to_build, rng, Some(self.id));
+        let left_behind = std::mem::take(&mut self.revealed);
+        self.explored.insert(self.id, left_behind);
+        self.id = to_build;
         self.base_tiles = new_data.tiles;
         self.exits = new_data.exits;
+        self.exit_conditions = new_data.exit_conditions;
+        self.exit_conditions
+            .retain(|(destination, _)| !self.unlocked_exits.contains(&(to_build, *destination)));
         self.features = new_data.features;
+        for (region, idx, tile) in self.changed_features.iter() {
+            if *region == to_build {
+                self.features[*idx] = *tile;
+            }
+        }
         self.name = new_data.name;
         self.spawns = new_data.spawns;
+        self.revealed = self
+            .explored
+            .remove(&to_build)
+            .unwrap_or_else(|| vec![false; NUM_TILES_X * NUM_TILES_Y]);
+        self.ambient = new_data.ambient;
 
         // Spawn the new one
-        self.spawn(assets, meshes, commands);
+        self.spawn(assets, archetypes, meshes, commands);
         Point::new(new_data.player_start.0, new_data.player_start.1)
     }
 
@@ -171,6 +206,83 @@ impl RegionMap {
         can_go
     }
 
+    /// Opens, closes or unlocks a door. Returns false if there is no door there.
+    pub fn use_door(
+        &mut self,
+        x: i32,
+        y: i32,
+        inventory: &mut Inventory,
+        console: &Console,
+        meshes: &mut Assets<Mesh>,
+    ) -> bool {
+        if !self.in_bounds(Point::new(x, y)) {
+            return false;
+        }
+        let idx = tile_index(x, y);
+        match self.features[idx] {
+            TileType::DoorClosed => {
+                self.features[idx] = TileType::DoorOpen;
+                console.write("You open the door.", Color::WHITE);
+            }
+            TileType::DoorOpen => {
+                self.features[idx] = TileType::DoorClosed;
+                console.write("You close the door.", Color::WHITE);
+            }
+            TileType::DoorLocked if inventory.keys > 0 => {
+                inventory.keys -= 1;
+                self.set_feature(idx, TileType::DoorOpen);
+                console.write("The key turns, and the door creaks open.", Color::YELLOW);
+            }
+            _ => return false,
+        }
+        self.rebuild_features(meshes);
+        true
+    }
+
+    /// Changes a feature for good, so it stays that way when the player comes back.
+    pub fn set_feature(&mut self, idx: usize, tile: TileType) {
+        self.features[idx] = tile;
+        let id = self.id;
+        self.changed_features
+            .retain(|(region, changed, _)| *region != id || *changed != idx);
+        self.changed_features.push((id, idx, tile));
+    }
+
+    /// Once a conditional exit has been passed, it stays open.
+    pub fn unlock_exit(&mut self, destination: usize) {
+        if self.exit_conditions.iter().any(|(d, _)| *d == destination) {
+            self.unlocked_exits.push((self.id, destination));
+        }
+    }
+
+    /// Call after changing features, so the display matches the map.
+    pub fn rebuild_features(&self, meshes: &mut Assets<Mesh>) {
+        if let Some(handle) = &self.mesh2 {
+            if let Some(mesh) = meshes.get_mut(handle) {
+                *mesh = TileMapLayer::new(1.5).build_mesh(&self.features);
+            }
+        }
+    }
+
+    pub fn jump_height(&self, x: i32, y: i32) -> u8 {
+        if !self.in_bounds(Point::new(x, y)) {
+            return u8::MAX;
+        }
+        let idx = tile_index(x, y);
+        let feature = match self.features[idx] {
+            TileType::ReferTo(refer_idx) => self.features[refer_idx],
+            feature => feature,
+        };
+        u8::max(self.base_tiles[idx].jump_height(), feature.jump_height())
+    }
+
+    /// A jump clears the tile in the middle if it is low enough, and has to land somewhere
+    /// you can stand. Water and chasms are flat, so you can jump them.
+    pub fn can_jump(&self, from: (i32, i32), to: (i32, i32)) -> bool {
+        let middle = ((from.0 + to.0) / 2, (from.1 + to.1) / 2);
+        self.jump_height(middle.0, middle.1) <= MAX_JUMP_HEIGHT && self.can_player_enter(to.0, to.1)
+    }
+
     pub fn interact(&self, x: i32, y: i32, console: &Console, health: &mut Health) {
         let idx = tile_index(x, y);
         self.base_tiles[idx].interact(console, health);
@@ -48,6 +48,10 @@ pub enum TileType {
     Fire,
     GoldEgg,
     Web,
+    DoorClosed,
+    DoorOpen,
+    DoorLocked,
+    Key,
 }
 
 impl TileType {
@@ -93,6 +97,10 @@ impl TileType {
             TileType::WaterV5 => 41,
             TileType::WaterV6 => 42,
             TileType::Web => 14,
+            TileType::DoorClosed => 43,
+            TileType::DoorOpen => 44,
+            TileType::DoorLocked => 45,
+            TileType::Key => 46,
             _ => 0,
         }
     }
@@ -128,6 +136,33 @@ impl TileType {
         }
     }
 
+    /// How tall a tile is: 0 is flat (including water), 1 is low enough to jump, 2 is too tall
+    pub fn jump_height(&self) -> u8 {
+        match self {
+            TileType::FenceHorizontal
+            | TileType::FenceVertical
+            | TileType::Bush
+            | TileType::Anvil
+            | TileType::Cauldron
+            | TileType::Fire => 1,
+            TileType::HayCart
+            | TileType::Barn
+            | TileType::LeftButte
+            | TileType::DoorClosed
+            | TileType::DoorLocked => 2,
+            _ => 0,
+        }
+    }
+
+    /// Radius, intensity and color of light given off by glowing features
+    pub fn light(&self) -> Option<(i32, f32, Color)> {
+        match self {
+            TileType::Fire => Some((4, 0.9, Color::rgb(1.0, 0.6, 0.2))),
+            TileType::GoldEgg => Some((2, 0.6, Color::rgb(1.0, 0.85, 0.3))),
+            _ => None,
+        }
+    }
+
     pub fn can_player_enter(&self) -> bool {
         match self {
             TileType::FenceHorizontal
@@ -143,7 +178,9 @@ impl TileType {
             | TileType::WaterV4
             | TileType::WaterV5
             | TileType::WaterV6
-            | TileType::Fire => false,
+            | TileType::Fire
+            | TileType::DoorClosed
+            | TileType::DoorLocked => false,
             _ => true,
         }
     }
@@ -169,6 +206,9 @@ impl TileType {
                 );
                 console.write("Farmer Tom's Magic Miracle Grow", Color::YELLOW);
             }
+            TileType::DoorLocked => {
+                console.write("The door is locked. You need a key.", Color::WHITE);
+            }
             TileType::Grain => {
                 console.write("Yummy, grain!", Color::GREEN);
                 health.current = health.max;
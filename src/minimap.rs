@@ -0,0 +1,154 @@
+use crate::{
+    actors::{Henry, Player},
+    factions::{Faction, Relationships},
+    fov::FieldOfView,
+    maps::{tile_index, RegionMap, TilePosition, TileType, NUM_TILES_X, NUM_TILES_Y},
+    GameElement, TimeStepResource,
+};
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use bracket_pathfinding::prelude::Point;
+
+const MINIMAP_SCALE: f32 = 3.0;
+
+#[derive(Component)]
+pub struct Minimap(Handle<Image>);
+
+pub fn setup_minimap(commands: &mut Commands, images: &mut Assets<Image>) {
+    let image = Image::new(
+        Extent3d {
+            width: NUM_TILES_X as u32,
+            height: NUM_TILES_Y as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        vec![0; NUM_TILES_X * NUM_TILES_Y * 4],
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    let handle = images.add(image);
+
+    let size = Vec2::new(
+        NUM_TILES_X as f32 * MINIMAP_SCALE,
+        NUM_TILES_Y as f32 * MINIMAP_SCALE,
+    );
+    commands
+        .spawn_bundle(SpriteBundle {
+            texture: handle.clone(),
+            sprite: Sprite {
+                custom_size: Some(size),
+                ..default()
+            },
+            // Bottom-right corner, underneath the region name
+            transform: Transform::from_xyz(
+                (1024.0 / 2.0) - (size.x / 2.0) - 4.0,
+                (0.0 - 768.0 / 2.0) + (size.y / 2.0) + 4.0,
+                5.0,
+            ),
+            ..default()
+        })
+        .insert(Minimap(handle))
+        .insert(GameElement);
+}
+
+fn tile_color(tile: TileType) -> [u8; 4] {
+    match tile {
+        TileType::Grass | TileType::Flower => [60, 140, 60, 255],
+        TileType::Dirt => [120, 90, 50, 255],
+        TileType::Road => [150, 130, 100, 255],
+        TileType::CaveFloor => [90, 80, 70, 255],
+        TileType::Bush => [20, 80, 20, 255],
+        TileType::FenceHorizontal | TileType::FenceVertical => [160, 110, 60, 255],
+        TileType::Grain => [230, 200, 60, 255],
+        TileType::Fire => [250, 120, 20, 255],
+        TileType::GoldEgg => [255, 215, 0, 255],
+        TileType::Web => [200, 200, 200, 255],
+        TileType::CobbleTL
+        | TileType::CobbleT
+        | TileType::CobbleTR
+        | TileType::CobbleL
+        | TileType::Cobble
+        | TileType::CobbleR
+        | TileType::CobbleBL
+        | TileType::CobbleB
+        | TileType::CobbleBR => [130, 130, 130, 255],
+        _ if !tile.can_player_enter() => [40, 40, 40, 255],
+        _ => [30, 60, 140, 255],
+    }
+}
+
+fn paint(data: &mut [u8], x: i32, y: i32, color: [u8; 4]) {
+    if x < 0 || x >= NUM_TILES_X as i32 || y < 0 || y >= NUM_TILES_Y as i32 {
+        return;
+    }
+    let offset = tile_index(x, y) * 4;
+    data[offset..offset + 4].copy_from_slice(&color);
+}
+
+pub fn update_minimap(
+    minimap: Query<&Minimap>,
+    player: Query<(&TilePosition, &FieldOfView), With<Player>>,
+    henry: Query<(&TilePosition, &FieldOfView), With<Henry>>,
+    actors: Query<(&TilePosition, &Faction)>,
+    relationships: Res<Relationships>,
+    map: Res<RegionMap>,
+    mut images: ResMut<Assets<Image>>,
+    timer: Res<TimeStepResource>,
+) {
+    if !timer.timer.finished() {
+        return;
+    }
+    for minimap in minimap.iter() {
+        if let Some(image) = images.get_mut(&minimap.0) {
+            let data = &mut image.data;
+
+            // Terrain, with features painted over the base tiles
+            for y in 0..NUM_TILES_Y as i32 {
+                for x in 0..NUM_TILES_X as i32 {
+                    let idx = tile_index(x, y);
+                    if !map.revealed[idx] {
+                        paint(data, x, y, [0, 0, 0, 255]);
+                        continue;
+                    }
+                    let tile = match map.features[idx] {
+                        TileType::None => map.base_tiles[idx],
+                        TileType::ReferTo(refer_idx) => map.features[refer_idx],
+                        feature => feature,
+                    };
+                    paint(data, x, y, tile_color(tile));
+                }
+            }
+
+            for (exit, _) in map.exits.iter() {
+                if map.revealed[*exit] {
+                    let x = (exit % NUM_TILES_X) as i32;
+                    let y = (exit / NUM_TILES_X) as i32;
+                    paint(data, x, y, [0, 220, 255, 255]);
+                }
+            }
+
+            // Hostiles only show up if you or Henry can see them
+            let henry = henry.get_single();
+            let player = player.get_single();
+            let hostiles = actors
+                .iter()
+                .filter(|(_, faction)| relationships.is_hostile(**faction, Faction::Player));
+            for (pos, _) in hostiles {
+                let pt = Point::new(pos.x, pos.y);
+                let seen_by_henry = matches!(henry, Ok((_, fov)) if fov.fov_set.contains(&pt));
+                let seen_by_player = matches!(player, Ok((_, fov)) if fov.fov_set.contains(&pt));
+                if seen_by_henry || seen_by_player {
+                    paint(data, pos.x, pos.y, [255, 0, 0, 255]);
+                }
+            }
+            if let Ok((henry_pos, _)) = henry {
+                paint(data, henry_pos.x, henry_pos.y, [255, 255, 0, 255]);
+            }
+
+            if let Ok((pos, _)) = player {
+                paint(data, pos.x, pos.y, [255, 255, 255, 255]);
+            }
+        }
+    }
+}
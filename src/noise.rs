@@ -0,0 +1,156 @@
+use crate::{
+    ai::{Memory, Sighting},
+    combat::{Dead, LerpAttack},
+    factions::{Faction, Relationships},
+    maps::{tile_index, LerpMove, RegionMap, TilePosition, NUM_TILES_X, NUM_TILES_Y},
+    status::{is_unconscious, StatusEffects},
+    stealth::Stealth,
+    TimeStepResource,
+};
+use bevy::prelude::*;
+use bracket_pathfinding::prelude::{Algorithm2D, BaseMap, DijkstraMap, Point, SmallVec};
+
+pub const WALK_NOISE: f32 = 2.0;
+pub const JUMP_NOISE: f32 = 8.0;
+pub const BUMP_NOISE: f32 = 6.0;
+pub const FIGHT_NOISE: f32 = 6.0;
+pub const SPIKE_NOISE: f32 = 7.0;
+
+/// Sound goes through walls, but it loses a lot doing it
+const WALL_DAMPING: f32 = 4.0;
+
+/// Something made a racket. Loudness is roughly how many open tiles away it can be heard.
+pub struct Noise {
+    pub position: (i32, i32),
+    pub loudness: f32,
+    pub source: Entity,
+}
+
+/// The map as sound sees it: every tile connects to its neighbours, walls just cost more
+struct SoundMap<'a> {
+    map: &'a RegionMap,
+}
+
+impl<'a> Algorithm2D for SoundMap<'a> {
+    fn dimensions(&self) -> Point {
+        Point::new(NUM_TILES_X, NUM_TILES_Y)
+    }
+
+    fn in_bounds(&self, pos: Point) -> bool {
+        self.map.in_bounds(pos)
+    }
+}
+
+impl<'a> BaseMap for SoundMap<'a> {
+    fn get_available_exits(&self, idx: usize) -> SmallVec<[(usize, f32); 10]> {
+        let mut exits = SmallVec::new();
+        let here = self.index_to_point2d(idx);
+        for delta in [
+            Point::new(-1, 0),
+            Point::new(1, 0),
+            Point::new(0, -1),
+            Point::new(0, 1),
+        ] {
+            let there = here + delta;
+            if self.map.in_bounds(there) {
+                let cost = if self.map.can_player_enter(there.x, there.y) {
+                    1.0
+                } else {
+                    WALL_DAMPING
+                };
+                exits.push((tile_index(there.x, there.y), cost));
+            }
+        }
+        exits
+    }
+}
+
+/// Movement and fighting are noisy; how noisy depends on what you're doing
+pub fn make_noise(
+    moves: Query<(Entity, &LerpMove, Option<&Stealth>), Added<LerpMove>>,
+    attacks: Query<(Entity, &LerpAttack), Added<LerpAttack>>,
+    mut noises: EventWriter<Noise>,
+) {
+    for (entity, lerp, stealth) in moves.iter() {
+        let loudness = if lerp.bump {
+            BUMP_NOISE
+        } else if lerp.jumping {
+            JUMP_NOISE
+        } else if stealth.is_some_and(|stealth| stealth.sneaking) {
+            // Tip-toeing chickens make no sound at all
+            continue;
+        } else {
+            WALK_NOISE
+        };
+        noises.send(Noise {
+            position: lerp.end,
+            loudness,
+            source: entity,
+        });
+    }
+    for (entity, attack) in attacks.iter() {
+        noises.send(Noise {
+            position: attack.start,
+            loudness: FIGHT_NOISE,
+            source: entity,
+        });
+    }
+}
+
+/// Anyone with a memory who hears a noise made by something they're after goes to take a look
+pub fn listen(
+    mut noises: EventReader<Noise>,
+    mut listeners: Query<
+        (
+            Entity,
+            &TilePosition,
+            &Faction,
+            &mut Memory,
+            Option<&StatusEffects>,
+        ),
+        Without<Dead>,
+    >,
+    factions: Query<&Faction>,
+    relationships: Res<Relationships>,
+    map: Res<RegionMap>,
+    timer: Res<TimeStepResource>,
+) {
+    let now = timer.ticks;
+    for noise in noises.iter() {
+        let source_faction = if let Ok(faction) = factions.get(noise.source) {
+            *faction
+        } else {
+            continue;
+        };
+        let interested: Vec<Entity> = listeners
+            .iter()
+            .filter(|(entity, _, faction, _, effects)| {
+                *entity != noise.source
+                    && !is_unconscious(*effects)
+                    && relationships.is_hostile(**faction, source_faction)
+            })
+            .map(|(entity, _, _, _, _)| entity)
+            .collect();
+        if interested.is_empty() {
+            continue;
+        }
+
+        let sound = SoundMap { map: &map };
+        let starts = vec![tile_index(noise.position.0, noise.position.1)];
+        let flood = DijkstraMap::new(NUM_TILES_X, NUM_TILES_Y, &starts, &sound, noise.loudness);
+        for entity in interested {
+            if let Ok((_, pos, _, mut memory, _)) = listeners.get_mut(entity) {
+                if flood.map[tile_index(pos.x, pos.y)] < noise.loudness {
+                    memory.sightings.insert(
+                        noise.source,
+                        Sighting {
+                            position: noise.position,
+                            seen_at: now,
+                        },
+                    );
+                    memory.search = None;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{
+    combat::{Dead, Health},
+    TimeStepResource,
+};
+use bevy::prelude::*;
+
+const REGEN_INTERVAL: u32 = 30;
+
+/// Anything temporary that happens to an actor, good or bad
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Effect {
+    /// Out cold: can't act, and nobody bothers with you
+    Unconscious,
+    /// Reeling: can't act, but still fair game
+    Stunned,
+    /// Can fight, but can't move
+    Rooted,
+    /// Moves twice as fast, and gets more turns
+    Hasted,
+    /// Gets a point of health back every so often
+    Regenerating,
+}
+
+/// What happens when an effect lands on someone who already has it
+enum Stacking {
+    /// Whichever would last longer
+    Refresh,
+    /// The durations add up
+    Extend,
+}
+
+impl Effect {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Effect::Unconscious => "out cold",
+            Effect::Stunned => "stunned",
+            Effect::Rooted => "webbed",
+            Effect::Hasted => "hasted",
+            Effect::Regenerating => "regenerating",
+        }
+    }
+
+    fn stacking(&self) -> Stacking {
+        match self {
+            Effect::Unconscious | Effect::Stunned | Effect::Rooted => Stacking::Refresh,
+            Effect::Hasted | Effect::Regenerating => Stacking::Extend,
+        }
+    }
+
+    /// How often the effect does something while it lasts, if it does anything at all
+    fn interval(&self) -> Option<u32> {
+        match self {
+            Effect::Regenerating => Some(REGEN_INTERVAL),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct StatusEffect {
+    pub effect: Effect,
+    pub ticks: u32,
+    /// Ticks since it landed, for effects that go off every so often
+    age: u32,
+}
+
+/// Every timed effect an actor is under. Actors without one are simply unaffected.
+#[derive(Component, Default, Debug)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    /// Returns true if the effect is new, rather than stacking onto one already there
+    pub fn apply(&mut self, effect: Effect, ticks: u32) -> bool {
+        if let Some(current) = self.effects.iter_mut().find(|e| e.effect == effect) {
+            match effect.stacking() {
+                Stacking::Refresh => current.ticks = u32::max(current.ticks, ticks),
+                Stacking::Extend => current.ticks = current.ticks.saturating_add(ticks),
+            }
+            return false;
+        }
+        self.effects.push(StatusEffect {
+            effect,
+            ticks,
+            age: 0,
+        });
+        true
+    }
+
+    /// Cuts an effect short. It wears off on the next tick if this takes it to zero.
+    pub fn shorten(&mut self, effect: Effect, ticks: u32) {
+        if let Some(current) = self.effects.iter_mut().find(|e| e.effect == effect) {
+            current.ticks = current.ticks.saturating_sub(ticks);
+        }
+    }
+
+    pub fn get(&self, effect: Effect) -> Option<&StatusEffect> {
+        self.effects.iter().find(|e| e.effect == effect)
+    }
+
+    pub fn has(&self, effect: Effect) -> bool {
+        self.get(effect).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &StatusEffect> {
+        self.effects.iter()
+    }
+
+    pub fn can_act(&self) -> bool {
+        !self.has(Effect::Unconscious) && !self.has(Effect::Stunned)
+    }
+
+    pub fn can_move(&self) -> bool {
+        self.can_act() && !self.has(Effect::Rooted)
+    }
+}
+
+/// For queries over things that might not have any effects at all
+pub fn is_unconscious(effects: Option<&StatusEffects>) -> bool {
+    effects.is_some_and(|effects| effects.has(Effect::Unconscious))
+}
+
+pub fn can_act(effects: Option<&StatusEffects>) -> bool {
+    effects.map_or(true, |effects| effects.can_act())
+}
+
+/// Counts effects down, and lets regeneration do its thing
+pub fn tick_status_effects(
+    mut actors: Query<(&mut StatusEffects, Option<&mut Health>), Without<Dead>>,
+    timer: Res<TimeStepResource>,
+) {
+    if !timer.timer.finished() {
+        return;
+    }
+    for (mut effects, mut health) in actors.iter_mut() {
+        for status in effects.effects.iter_mut() {
+            status.age += 1;
+            let due = status
+                .effect
+                .interval()
+                .is_some_and(|interval| status.age % interval == 0);
+            if due && status.effect == Effect::Regenerating {
+                if let Some(health) = health.as_mut() {
+                    health.current = i32::min(health.current + 1, health.max);
+                }
+            }
+            status.ticks = status.ticks.saturating_sub(1);
+        }
+        effects.effects.retain(|status| status.ticks > 0);
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{
+    assets::GameAssets,
+    combat::Dead,
+    factions::{Faction, Relationships},
+    fov::FieldOfView,
+    lighting::LightMap,
+    maps::{tile_index, LerpMove, MapElement, RegionMap, TilePosition, TileType},
+    status::{is_unconscious, StatusEffects},
+    GameElement, TimeStepResource,
+};
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bracket_pathfinding::prelude::{Algorithm2D, DistanceAlg, Point};
+
+/// Awareness at which an observer starts to wonder what that was
+pub const SUSPICIOUS: f32 = 0.3;
+/// Awareness at which an observer knows exactly where you are
+pub const NOTICED: f32 = 1.0;
+
+const GRAIN_COVER: f32 = 0.5;
+const BUSH_COVER: f32 = 0.6;
+const SHADOW_COVER: f32 = 0.6;
+const SNEAK_COVER: f32 = 0.6;
+/// Ticks to wait between steps while sneaking
+pub const SNEAK_PAUSE: u32 = 8;
+const DETECTION_RATE: f32 = 0.04;
+const FORGET_RATE: f32 = 0.01;
+
+/// Lets the player hide. Concealment scales how far away observers can pick you out.
+#[derive(Component)]
+pub struct Stealth {
+    pub concealment: f32,
+    pub sneaking: bool,
+    pub pause: u32,
+}
+
+impl Default for Stealth {
+    fn default() -> Self {
+        Self {
+            concealment: 1.0,
+            sneaking: false,
+            pause: 0,
+        }
+    }
+}
+
+/// How sure an observer is about each stealthy thing it has caught a glimpse of
+#[derive(Component, Default)]
+pub struct Awareness {
+    pub levels: HashMap<Entity, f32>,
+}
+
+impl Awareness {
+    pub fn of(&self, target: Entity) -> f32 {
+        self.levels.get(&target).copied().unwrap_or(0.0)
+    }
+
+    pub fn noticed(&self, target: Entity) -> bool {
+        self.of(target) >= NOTICED
+    }
+
+    pub fn suspicious(&self, target: Entity) -> bool {
+        self.of(target) >= SUSPICIOUS
+    }
+}
+
+fn is_bush(map: &RegionMap, x: i32, y: i32) -> bool {
+    if !map.in_bounds(Point::new(x, y)) {
+        return false;
+    }
+    let idx = tile_index(x, y);
+    map.base_tiles[idx] == TileType::Bush || map.features[idx] == TileType::Bush
+}
+
+pub fn update_stealth(
+    mut player: Query<(&TilePosition, &mut Stealth, Option<&LerpMove>)>,
+    keyboard: Res<Input<KeyCode>>,
+    map: Res<RegionMap>,
+    light: Res<LightMap>,
+    timer: Res<TimeStepResource>,
+) {
+    for (pos, mut stealth, moving) in player.iter_mut() {
+        stealth.sneaking = keyboard.pressed(KeyCode::LShift) || keyboard.pressed(KeyCode::RShift);
+        if !timer.timer.finished() {
+            continue;
+        }
+        stealth.pause = stealth.pause.saturating_sub(1);
+
+        let mut concealment = 1.0;
+        if map.features[tile_index(pos.x, pos.y)] == TileType::Grain {
+            concealment *= GRAIN_COVER;
+        }
+        let near_bush = (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .any(|(dx, dy)| is_bush(&map, pos.x + dx, pos.y + dy));
+        if near_bush {
+            concealment *= BUSH_COVER;
+        }
+        if !light.is_lit(pos.x, pos.y) {
+            concealment *= SHADOW_COVER;
+        }
+        if stealth.sneaking || moving.is_none() {
+            concealment *= SNEAK_COVER;
+        }
+        stealth.concealment = concealment;
+    }
+}
+
+/// Observers slowly make up their minds about hidden things in their field of view
+pub fn detect(
+    mut observers: Query<
+        (
+            Entity,
+            &TilePosition,
+            &FieldOfView,
+            &Faction,
+            &mut Awareness,
+            Option<&StatusEffects>,
+        ),
+        Without<Dead>,
+    >,
+    targets: Query<(Entity, &TilePosition, &Faction, &Stealth), Without<Dead>>,
+    relationships: Res<Relationships>,
+    timer: Res<TimeStepResource>,
+) {
+    if !timer.timer.finished() {
+        return;
+    }
+    for (observer, pos, fov, faction, mut awareness, effects) in observers.iter_mut() {
+        if is_unconscious(effects) {
+            continue;
+        }
+        for (target, target_pos, target_faction, stealth) in targets.iter() {
+            if target == observer || !relationships.is_hostile(*faction, *target_faction) {
+                continue;
+            }
+            let distance = DistanceAlg::Pythagoras.distance2d(
+                Point::new(pos.x, pos.y),
+                Point::new(target_pos.x, target_pos.y),
+            );
+            let visible = fov
+                .fov_set
+                .contains(&Point::new(target_pos.x, target_pos.y));
+            let detection_range = fov.range as f32 * stealth.concealment;
+
+            let level = awareness.of(target);
+            let level = if visible && level >= NOTICED {
+                // Once you've been spotted, hiding in plain sight doesn't help
+                NOTICED
+            } else if visible && distance <= detection_range {
+                // Closer is quicker
+                let closeness = 1.0 - distance / (detection_range + 1.0);
+                f32::min(NOTICED, level + DETECTION_RATE * (1.0 + 2.0 * closeness))
+            } else {
+                level - FORGET_RATE
+            };
+            if level > 0.0 {
+                awareness.levels.insert(target, level);
+            } else {
+                awareness.levels.remove(&target);
+            }
+        }
+        awareness
+            .levels
+            .retain(|target, _| targets.get(*target).is_ok());
+    }
+}
+
+#[derive(Component)]
+pub struct AwarenessIndicator(Entity);
+
+/// "?" over anything that's suspicious, "!" over anything that has spotted you
+pub fn update_awareness_indicators(
+    observers: Query<
+        (Entity, &Awareness, &Transform, &Visibility),
+        (Without<AwarenessIndicator>, Without<Dead>),
+    >,
+    mut indicators: Query<
+        (Entity, &AwarenessIndicator, &mut Transform, &mut Text),
+        Without<Awareness>,
+    >,
+    assets: Res<GameAssets>,
+    mut commands: Commands,
+) {
+    let mark = |awareness: &Awareness| {
+        let level = awareness.levels.values().copied().fold(0.0, f32::max);
+        if level >= NOTICED {
+            Some(("!", Color::RED))
+        } else if level >= SUSPICIOUS {
+            Some(("?", Color::YELLOW))
+        } else {
+            None
+        }
+    };
+
+    let mut marked = HashSet::default();
+    for (indicator, owner, mut transform, mut text) in indicators.iter_mut() {
+        let shown = observers
+            .get(owner.0)
+            .ok()
+            .filter(|(_, _, _, visibility)| visibility.is_visible)
+            .and_then(|(_, awareness, owner_transform, _)| {
+                mark(awareness).map(|mark| (mark, owner_transform))
+            });
+        if let Some(((symbol, color), owner_transform)) = shown {
+            marked.insert(owner.0);
+            transform.translation = indicator_position(owner_transform);
+            text.sections[0].value = symbol.to_string();
+            text.sections[0].style.color = color;
+        } else {
+            commands.entity(indicator).despawn();
+        }
+    }
+
+    for (owner, awareness, owner_transform, visibility) in observers.iter() {
+        if marked.contains(&owner) || !visibility.is_visible {
+            continue;
+        }
+        if let Some((symbol, color)) = mark(awareness) {
+            commands
+                .spawn_bundle(Text2dBundle {
+                    text: Text::with_section(
+                        symbol,
+                        TextStyle {
+                            font: assets.font.clone(),
+                            font_size: 20.0,
+                            color,
+                        },
+                        TextAlignment {
+                            horizontal: HorizontalAlign::Center,
+                            vertical: VerticalAlign::Center,
+                        },
+                    ),
+                    transform: Transform::from_translation(indicator_position(owner_transform)),
+                    ..default()
+                })
+                .insert(AwarenessIndicator(owner))
+                .insert(MapElement)
+                .insert(GameElement);
+        }
+    }
+}
+
+fn indicator_position(owner: &Transform) -> Vec3 {
+    Vec3::new(owner.translation.x, owner.translation.y + 30.0, 4.0)
+}
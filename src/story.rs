@@ -0,0 +1,6 @@
+/// Things that have happened in the world, that other parts of the game care about.
+#[derive(Default)]
+pub struct StoryFlags {
+    pub white_wolf_defeated: bool,
+    pub chickens_herded: bool,
+}
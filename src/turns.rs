@@ -0,0 +1,140 @@
+use crate::{
+    actors::Player,
+    combat::{Dead, LerpAttack},
+    factions::{Faction, Relationships},
+    maps::{LerpMove, TilePosition},
+    status::{Effect, StatusEffects},
+    TimeStepResource,
+};
+use bevy::prelude::*;
+
+/// Energy it takes to do anything. An actor with this much speed keeps pace with the player.
+pub const ACTION_COST: i32 = 10;
+/// Ticks with nothing moving before the world hands control back to the player
+const SETTLE_TICKS: u32 = 2;
+
+/// Picked on the main menu
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlayMode {
+    RealTime,
+    TurnBased,
+}
+
+/// In turn-based mode, the world clock only runs while a turn plays out
+pub struct Turns {
+    pub mode: PlayMode,
+    pub waiting: bool,
+    settle: u32,
+}
+
+impl Default for Turns {
+    fn default() -> Self {
+        Self::new(PlayMode::RealTime)
+    }
+}
+
+impl Turns {
+    pub fn new(mode: PlayMode) -> Self {
+        Self {
+            mode,
+            waiting: false,
+            settle: 0,
+        }
+    }
+
+    pub fn is_turn_based(&self) -> bool {
+        self.mode == PlayMode::TurnBased
+    }
+
+    /// The player has acted, so let the world play out
+    pub fn start_turn(&mut self) {
+        self.waiting = false;
+        self.settle = 0;
+    }
+}
+
+/// Actors gain speed worth of energy every round, and spend it to act
+#[derive(Component)]
+pub struct Initiative {
+    pub speed: i32,
+    pub energy: i32,
+}
+
+impl Initiative {
+    pub fn new(speed: i32) -> Self {
+        Self {
+            speed,
+            energy: speed,
+        }
+    }
+
+    pub fn can_act(&self) -> bool {
+        self.energy >= ACTION_COST
+    }
+}
+
+/// Once everything has stopped moving, either hand control to the player or start a new round
+pub fn advance_turns(
+    mut turns: ResMut<Turns>,
+    mut actors: Query<(
+        &mut Initiative,
+        Option<&Player>,
+        Option<&StatusEffects>,
+        Option<&TilePosition>,
+    )>,
+    busy: Query<(), Or<(With<LerpMove>, With<LerpAttack>)>>,
+    others: Query<(&TilePosition, &Faction), Without<Dead>>,
+    relationships: Res<Relationships>,
+    timer: Res<TimeStepResource>,
+) {
+    if !turns.is_turn_based() || turns.waiting || !timer.timer.finished() {
+        return;
+    }
+    if !busy.is_empty() {
+        turns.settle = 0;
+        return;
+    }
+    turns.settle += 1;
+    if turns.settle < SETTLE_TICKS {
+        return;
+    }
+    turns.settle = 0;
+
+    let mut player_ready = false;
+    for (mut initiative, player, effects, pos) in actors.iter_mut() {
+        if player.is_none() || !initiative.can_act() {
+            continue;
+        }
+        // Stunned, or webbed with nothing in reach to peck at: the round goes by without you,
+        // since the clock has to run for it to wear off
+        let in_reach = pos.is_some_and(|pos| {
+            others.iter().any(|(other, faction)| {
+                relationships.is_hostile(Faction::Player, *faction)
+                    && (other.x - pos.x).abs() <= 1
+                    && (other.y - pos.y).abs() <= 1
+            })
+        });
+        let stuck =
+            effects.is_some_and(|effects| !effects.can_act() || (!effects.can_move() && !in_reach));
+        if stuck {
+            initiative.energy = initiative.energy.min(ACTION_COST - 1);
+        } else {
+            player_ready = true;
+        }
+    }
+    if player_ready {
+        turns.waiting = true;
+        return;
+    }
+    // Unspent turns aren't banked, only the change
+    for (mut initiative, _, effects, _) in actors.iter_mut() {
+        // Haste doubles what you get each round
+        let hasted = effects.is_some_and(|effects| effects.has(Effect::Hasted));
+        let gain = if hasted {
+            initiative.speed * 2
+        } else {
+            initiative.speed
+        };
+        initiative.energy = initiative.energy.min(ACTION_COST - 1) + gain;
+    }
+}
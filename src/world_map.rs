@@ -0,0 +1,239 @@
+use crate::{
+    assets::GameAssets,
+    maps::{MapToBuild, RegionMap},
+    GameState,
+};
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+pub struct VisitedRegion {
+    pub id: MapToBuild,
+    pub name: String,
+    pub exits: Vec<MapToBuild>,
+}
+
+/// Remembers every region the player has been to, and where its exits lead.
+pub struct WorldAtlas {
+    pub regions: Vec<VisitedRegion>,
+    pub current: MapToBuild,
+}
+
+impl WorldAtlas {
+    pub fn new(map: &RegionMap) -> Self {
+        let mut atlas = Self {
+            regions: Vec::new(),
+            current: map.id,
+        };
+        atlas.visit(map);
+        atlas
+    }
+
+    pub fn visit(&mut self, map: &RegionMap) {
+        self.current = map.id;
+        let mut exits: Vec<MapToBuild> = Vec::new();
+        for (_, destination) in map.exits.iter() {
+            let destination = MapToBuild::from_exit(*destination);
+            if !exits.contains(&destination) {
+                exits.push(destination);
+            }
+        }
+
+        if let Some(region) = self.regions.iter_mut().find(|r| r.id == map.id) {
+            region.name = map.name.clone();
+            region.exits = exits;
+        } else {
+            self.regions.push(VisitedRegion {
+                id: map.id,
+                name: map.name.clone(),
+                exits,
+            });
+        }
+    }
+
+    fn region(&self, id: MapToBuild) -> Option<&VisitedRegion> {
+        self.regions.iter().find(|r| r.id == id)
+    }
+
+    /// Lay out the known graph in columns, by distance from the first region visited.
+    fn layout(&self) -> Vec<(MapToBuild, usize, usize)> {
+        let mut placed: Vec<(MapToBuild, usize, usize)> = Vec::new();
+        let mut column_sizes: Vec<usize> = Vec::new();
+        let mut open = VecDeque::new();
+        if let Some(first) = self.regions.first() {
+            open.push_back((first.id, 0));
+        }
+        while let Some((id, depth)) = open.pop_front() {
+            if placed.iter().any(|(p, _, _)| *p == id) {
+                continue;
+            }
+            if column_sizes.len() <= depth {
+                column_sizes.push(0);
+            }
+            placed.push((id, depth, column_sizes[depth]));
+            column_sizes[depth] += 1;
+            if let Some(region) = self.region(id) {
+                for exit in region.exits.iter() {
+                    open.push_back((*exit, depth + 1));
+                }
+            }
+        }
+        placed
+    }
+}
+
+#[derive(Component)]
+pub struct WorldMapElement;
+
+// The key press is consumed, so the map doesn't see it and close in the same frame.
+pub fn open_world_map(mut keyboard: ResMut<Input<KeyCode>>, mut state: ResMut<State<GameState>>) {
+    if keyboard.clear_just_pressed(KeyCode::M) {
+        let _ = state.push(GameState::WorldMap);
+    }
+}
+
+pub fn world_map(mut keyboard: ResMut<Input<KeyCode>>, mut state: ResMut<State<GameState>>) {
+    if keyboard.clear_just_pressed(KeyCode::M) || keyboard.clear_just_pressed(KeyCode::Escape) {
+        let _ = state.pop();
+    }
+}
+
+pub fn exit_world_map(mut commands: Commands, query: Query<Entity, With<WorldMapElement>>) {
+    query.iter().for_each(|e| commands.entity(e).despawn());
+}
+
+pub fn start_world_map(mut commands: Commands, assets: Res<GameAssets>, atlas: Res<WorldAtlas>) {
+    const COLUMN_WIDTH: f32 = 260.0;
+    const ROW_HEIGHT: f32 = 96.0;
+
+    // Backdrop
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(0.0, 0.0, 0.0, 0.9),
+                custom_size: Some(Vec2::new(1024.0, 768.0)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, 20.0),
+            ..default()
+        })
+        .insert(WorldMapElement);
+
+    spawn_label(
+        &mut commands,
+        &assets,
+        "World Map - press M to return",
+        Vec3::new(0.0, 340.0, 23.0),
+        32.0,
+        Color::YELLOW,
+    );
+
+    let layout = atlas.layout();
+    let node_position = |column: usize, row: usize| {
+        Vec2::new(
+            -400.0 + (column as f32 * COLUMN_WIDTH),
+            240.0 - (row as f32 * ROW_HEIGHT),
+        )
+    };
+
+    // Links between regions
+    for (id, column, row) in layout.iter() {
+        if let Some(region) = atlas.region(*id) {
+            let start = node_position(*column, *row);
+            for exit in region.exits.iter() {
+                if let Some((_, dest_column, dest_row)) = layout.iter().find(|(d, _, _)| d == exit)
+                {
+                    let end = node_position(*dest_column, *dest_row);
+                    let delta = end - start;
+                    let center = start + (delta / 2.0);
+                    let mut transform = Transform::from_xyz(center.x, center.y, 21.0);
+                    transform.rotation = Quat::from_rotation_z(delta.y.atan2(delta.x));
+                    commands
+                        .spawn_bundle(SpriteBundle {
+                            sprite: Sprite {
+                                color: Color::GRAY,
+                                custom_size: Some(Vec2::new(delta.length(), 4.0)),
+                                ..default()
+                            },
+                            transform,
+                            ..default()
+                        })
+                        .insert(WorldMapElement);
+                }
+            }
+        }
+    }
+
+    // The regions themselves
+    for (id, column, row) in layout.iter() {
+        let pos = node_position(*column, *row);
+        let (name, color) = if let Some(region) = atlas.region(*id) {
+            let color = if *id == atlas.current {
+                Color::rgb(0.8, 0.6, 0.1)
+            } else {
+                Color::rgb(0.2, 0.5, 0.2)
+            };
+            (region.name.clone(), color)
+        } else {
+            ("???".to_string(), Color::rgb(0.2, 0.2, 0.2))
+        };
+
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(Vec2::new(200.0, 48.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(pos.x, pos.y, 22.0),
+                ..default()
+            })
+            .insert(WorldMapElement);
+
+        spawn_label(
+            &mut commands,
+            &assets,
+            &name,
+            Vec3::new(pos.x, pos.y, 23.0),
+            20.0,
+            Color::WHITE,
+        );
+        if *id == atlas.current {
+            spawn_label(
+                &mut commands,
+                &assets,
+                "You are here",
+                Vec3::new(pos.x, pos.y - 36.0, 23.0),
+                16.0,
+                Color::YELLOW,
+            );
+        }
+    }
+}
+
+fn spawn_label(
+    commands: &mut Commands,
+    assets: &GameAssets,
+    text: &str,
+    pos: Vec3,
+    font_size: f32,
+    color: Color,
+) {
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                text,
+                TextStyle {
+                    font: assets.font.clone(),
+                    font_size,
+                    color,
+                },
+                TextAlignment {
+                    horizontal: HorizontalAlign::Center,
+                    vertical: VerticalAlign::Center,
+                },
+            ),
+            transform: Transform::from_translation(pos),
+            ..default()
+        })
+        .insert(WorldMapElement);
+}